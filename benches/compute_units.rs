@@ -2,7 +2,6 @@ use mollusk_svm::result::Check;
 use mollusk_svm::{ program, Mollusk };
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
 use solana_sdk::account::{ Account, WritableAccount };
-use solana_sdk::instruction::{ AccountMeta, Instruction };
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::program_option::COption;
 use solana_sdk::program_pack::Pack;
@@ -10,9 +9,17 @@ use solana_sdk::pubkey::Pubkey;
 extern crate alloc;
 use alloc::vec;
 
-use pinocchio_fundraiser::instruction::{ InitializeIxData, ContributeIxData };
-use pinocchio_fundraiser::state::{ Contributor, Fundraiser };
-use pinocchio_fundraiser::utils::to_bytes;
+use pinocchio_fundraiser::client::{
+    find_contributor_address,
+    find_fundraiser_address,
+    find_receipt_mint_address,
+    get_associated_token_address,
+    CheckContribution,
+    Contribute,
+    Initialize,
+};
+use pinocchio_fundraiser::constants::RECEIPT_RATE_SCALER;
+use pinocchio_fundraiser::instruction::InitializeIxData;
 
 pub const PROGRAM: Pubkey = Pubkey::new_from_array(pinocchio_fundraiser::ID);
 
@@ -52,7 +59,7 @@ fn main() {
         maker,
         _contributor, // We'll use a new one
         fundraiser,
-        fundraiser_bump,
+        _fundraiser_bump, // Derived by the builders
         mint_to_raise,
         vault,
         _system_program, // Already defined
@@ -67,22 +74,24 @@ fn main() {
     ) = setup_fundraiser(&mollusk);
 
     // 1. Initialize instruction
-    let init_instruction = create_initialize_instruction(
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let init_instruction = (Initialize {
         maker,
         mint_to_raise,
-        fundraiser,
-        vault,
-        fundraiser_bump,
-        system_program,
-        token_program,
-        10_000_000, // Amount to raise (10 tokens)
-        1 // Duration (1 day)
-    );
+        params: InitializeIxData {
+            amount: 10_000_000, // Amount to raise (10 tokens)
+            duration: 1, // Duration (1 day)
+            receipt_rate: RECEIPT_RATE_SCALER,
+            ..Default::default()
+        },
+        metadata: None,
+    }).instruction();
     let init_accounts = vec![
         (maker, maker_account.clone()),
         (mint_to_raise, mint_to_raise_account.clone()),
         (fundraiser, fundraiser_account.clone()),
         (vault, vault_account.clone()),
+        (receipt_mint, Account::new(0, 0, &system_program)),
         (system_program, system_account.clone()),
         (token_program, token_account.clone())
     ];
@@ -101,6 +110,7 @@ fn main() {
     vault_account.refresh(&vault, init_result.clone());
     system_account.refresh(&system_program, init_result.clone());
     token_account.refresh(&token_program, init_result.clone());
+    let mut receipt_mint_account = init_result.get_account(&receipt_mint).unwrap().clone();
 
     // 2. First contributor setup and contribution
     let contributor = Pubkey::new_unique();
@@ -108,7 +118,7 @@ fn main() {
 
     let (
         contributor_acc,
-        contributor_bump,
+        _,
         contributor_ata,
         mut contributor_acc_account,
         mut contributor_ata_account,
@@ -121,19 +131,13 @@ fn main() {
         token_program
     );
 
-    let contribute_instruction = create_contribute_instruction(
+    let (receipt_ata, mut receipt_ata_account) = get_receipt_ata(&mollusk, contributor, fundraiser);
+    let contribute_instruction = Contribute::new(
         contributor,
+        maker,
         mint_to_raise,
-        fundraiser,
-        contributor_acc,
-        contributor_ata,
-        vault,
-        contributor_bump,
-        fundraiser_bump,
-        system_program,
-        token_program,
         1_000_000 // 1 token
-    );
+    ).instruction();
     let contribute_accounts = vec![
         (contributor, contributor_account.clone()),
         (mint_to_raise, mint_to_raise_account.clone()),
@@ -142,7 +146,9 @@ fn main() {
         (contributor_ata, contributor_ata_account.clone()),
         (vault, vault_account.clone()),
         (system_program, system_account.clone()),
-        (token_program, token_account.clone())
+        (token_program, token_account.clone()),
+        (receipt_mint, receipt_mint_account.clone()),
+        (receipt_ata, receipt_ata_account.clone())
     ];
 
     // Execute first contribution
@@ -161,6 +167,8 @@ fn main() {
     contributor_ata_account.refresh(&contributor_ata, contribute_result.clone());
     system_account.refresh(&system_program, contribute_result.clone());
     token_account.refresh(&token_program, contribute_result.clone());
+    receipt_mint_account.refresh(&receipt_mint, contribute_result.clone());
+    receipt_ata_account.refresh(&receipt_ata, contribute_result.clone());
 
     // 3. Make 9 more contributions from different contributors (like in test_checker)
     for _ in 0..9 {
@@ -171,7 +179,7 @@ fn main() {
 
         let (
             new_contributor_acc,
-            new_contributor_bump,
+            _,
             new_contributor_ata,
             mut new_contributor_acc_account,
             mut new_contributor_ata_account,
@@ -184,19 +192,17 @@ fn main() {
             token_program
         );
 
-        let contribute_instruction = create_contribute_instruction(
+        let (new_receipt_ata, mut new_receipt_ata_account) = get_receipt_ata(
+            &mollusk,
+            new_contributor,
+            fundraiser
+        );
+        let contribute_instruction = Contribute::new(
             new_contributor,
+            maker,
             mint_to_raise,
-            fundraiser,
-            new_contributor_acc,
-            new_contributor_ata,
-            vault,
-            new_contributor_bump,
-            fundraiser_bump,
-            system_program,
-            token_program,
             1_000_000 // 1 token
-        );
+        ).instruction();
 
         let contribute_accounts = vec![
             (new_contributor, new_contributor_account.clone()),
@@ -206,7 +212,9 @@ fn main() {
             (new_contributor_ata, new_contributor_ata_account.clone()),
             (vault, vault_account.clone()),
             (system_program, system_account.clone()),
-            (token_program, token_account.clone())
+            (token_program, token_account.clone()),
+            (receipt_mint, receipt_mint_account.clone()),
+            (new_receipt_ata, new_receipt_ata_account.clone())
         ];
 
         // Execute additional contribution
@@ -225,10 +233,12 @@ fn main() {
         new_contributor_ata_account.refresh(&new_contributor_ata, contribute_result.clone());
         system_account.refresh(&system_program, contribute_result.clone());
         token_account.refresh(&token_program, contribute_result.clone());
+        receipt_mint_account.refresh(&receipt_mint, contribute_result.clone());
+        new_receipt_ata_account.refresh(&new_receipt_ata, contribute_result.clone());
     }
 
     // 4. Setup maker ATA for checker
-    let maker_ata = Pubkey::new_unique();
+    let maker_ata = get_associated_token_address(&maker, &mint_to_raise);
     let mut maker_ata_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
@@ -251,15 +261,11 @@ fn main() {
         .unwrap();
 
     // 5. Checker instruction (after 10 contributions)
-    let checker_instruction = create_checker_instruction(
+    let checker_instruction = (CheckContribution {
         maker,
         mint_to_raise,
-        fundraiser,
-        vault,
-        maker_ata,
-        system_program,
-        token_program
-    );
+        uses_registry: false,
+    }).instruction();
     let checker_accounts = vec![
         (maker, maker_account.clone()),
         (mint_to_raise, mint_to_raise_account.clone()),
@@ -282,118 +288,8 @@ fn main() {
         .execute();
 }
 
-fn create_initialize_instruction(
-    maker: Pubkey,
-    mint_to_raise: Pubkey,
-    fundraiser: Pubkey,
-    vault: Pubkey,
-    fundraiser_bump: u8,
-    system_program: Pubkey,
-    token_program: Pubkey,
-    amount: u64,
-    duration: u8
-) -> Instruction {
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(maker, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, true),
-        AccountMeta::new(vault, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
-
-    // Create instruction data
-    let ix_data = InitializeIxData {
-        amount,
-        duration,
-        bump: fundraiser_bump,
-    };
-
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![0]; // Ix discriminator = 0
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(&ix_data) });
-
-    // Create instruction
-    Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts)
-}
-
-fn create_contribute_instruction(
-    contributor: Pubkey,
-    mint_to_raise: Pubkey,
-    fundraiser: Pubkey,
-    contributor_acc: Pubkey,
-    contributor_ata: Pubkey,
-    vault: Pubkey,
-    contributor_bump: u8,
-    fundraiser_bump: u8,
-    system_program: Pubkey,
-    token_program: Pubkey,
-    amount: u64
-) -> Instruction {
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(contributor, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, false),
-        AccountMeta::new(contributor_acc, true),
-        AccountMeta::new(contributor_ata, false),
-        AccountMeta::new(vault, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
-
-    // Create instruction data
-    let ix_data = ContributeIxData {
-        amount,
-        contributor_bump,
-        fundraiser_bump,
-    };
-
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![1]; // Ix discriminator = 1
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(&ix_data) });
-
-    // Create instruction
-    Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts)
-}
-
-fn create_checker_instruction(
-    maker: Pubkey,
-    mint_to_raise: Pubkey,
-    fundraiser: Pubkey,
-    vault: Pubkey,
-    maker_ata: Pubkey,
-    system_program: Pubkey,
-    token_program: Pubkey
-) -> Instruction {
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(maker, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, true),
-        AccountMeta::new(vault, false),
-        AccountMeta::new(maker_ata, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
-
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![2]; // Ix discriminator = 2
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(
-            &(InitializeIxData {
-                amount: 0,
-                duration: 0,
-                bump: 0,
-            })
-        ) });
-
-    // Create instruction
-    Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts)
-}
-
-/// Setup common fundraiser accounts
-pub fn setup_fundraiser(mollusk: &Mollusk) -> (
+/// Keys, bump and accounts returned by `setup_fundraiser`
+pub type FundraiserSetup = (
     // Pubkeys
     Pubkey,
     Pubkey,
@@ -411,7 +307,10 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
     Account,
     Account,
     Account,
-) {
+);
+
+/// Setup common fundraiser accounts
+pub fn setup_fundraiser(mollusk: &Mollusk) -> FundraiserSetup {
     // Setup system and token programs
     let (system_program, system_account) = program::keyed_account_for_system_program();
     let (token_program, token_account) = get_spl_token_program();
@@ -423,10 +322,7 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
     let contributor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
 
     // Derive PDAs
-    let (fundraiser, fundraiser_bump) = Pubkey::find_program_address(
-        &[Fundraiser::SEED.as_bytes(), &maker.to_bytes()],
-        &PROGRAM
-    );
+    let (fundraiser, fundraiser_bump) = find_fundraiser_address(&maker);
 
     // Create empty fundraiser account (will be initialized later)
     let fundraiser_account = Account::new(0, 0, &system_program);
@@ -452,7 +348,7 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
         .unwrap();
 
     // Create vault account
-    let vault = Pubkey::new_from_array([0x04; 32]);
+    let vault = get_associated_token_address(&fundraiser, &mint_to_raise);
     let mut vault_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
@@ -495,6 +391,33 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
     )
 }
 
+/// Get an empty receipt token account for the contributor
+pub fn get_receipt_ata(mollusk: &Mollusk, contributor: Pubkey, fundraiser: Pubkey) -> (Pubkey, Account) {
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let receipt_ata = get_associated_token_address(&contributor, &receipt_mint);
+    let mut receipt_ata_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &spl_token::ID
+    );
+    solana_sdk::program_pack::Pack
+        ::pack(
+            spl_token::state::Account {
+                amount: 0,
+                mint: receipt_mint,
+                owner: contributor,
+                delegate: COption::None,
+                state: spl_token::state::AccountState::Initialized,
+                close_authority: COption::None,
+                is_native: COption::None,
+                delegated_amount: 0,
+            },
+            receipt_ata_account.data_as_mut_slice()
+        )
+        .unwrap();
+    (receipt_ata, receipt_ata_account)
+}
+
 pub fn get_spl_token_program() -> (Pubkey, Account) {
     (spl_token::ID, program::create_program_account_loader_v3(&spl_token::ID))
 }
@@ -503,7 +426,7 @@ pub fn get_spl_token_program() -> (Pubkey, Account) {
 pub fn get_new_contributor_account(_mollusk: &Mollusk, system_program: Pubkey) -> (Pubkey, Account) {
     let contributor = Pubkey::new_unique();
     let contributor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
-    return (contributor, contributor_account);
+    (contributor, contributor_account)
 }
 
 /// Setup contributor accounts
@@ -516,16 +439,13 @@ pub fn setup_contributor(
     token_program: Pubkey
 ) -> (Pubkey, u8, Pubkey, Account, Account) {
     // Derive contributor PDA
-    let (contributor_acc, contributor_bump) = Pubkey::find_program_address(
-        &[Contributor::SEED.as_bytes(), &fundraiser.to_bytes(), &contributor.to_bytes()],
-        &PROGRAM
-    );
+    let (contributor_acc, contributor_bump) = find_contributor_address(&fundraiser, &contributor);
 
     // Create empty contributor account (will be initialized during contribute)
     let contributor_acc_account = Account::new(0, 0, &system_program);
 
     // Create contributor ATA
-    let contributor_ata = Pubkey::new_unique();
+    let contributor_ata = get_associated_token_address(&contributor, &mint_to_raise);
     let mut contributor_ata_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
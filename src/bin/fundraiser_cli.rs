@@ -0,0 +1,393 @@
+//! Offline companion for operating fundraisers. Builds unsigned
+//! transactions for air-gapped signing, decodes account dumps and derives
+//! program addresses without ever talking to a cluster.
+
+use std::{ collections::HashMap, fs, process::ExitCode, str::FromStr, time::SystemTime };
+
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use solana_program::{ hash::Hash, instruction::Instruction, message::Message, pubkey::Pubkey };
+use solana_transaction::Transaction;
+
+use pinocchio_fundraiser::{
+    client::{
+        find_contributor_address,
+        find_fundraiser_address,
+        find_metadata_address,
+        find_receipt_mint_address,
+        find_registry_address,
+        get_associated_token_address,
+        AllowlistProof,
+        CheckContribution,
+        Contribute,
+        ContributorAccount,
+        FundraiserAccount,
+        Initialize,
+        Refund,
+    },
+    constants::{ NO_TIER, RECEIPT_RATE_SCALER },
+    error::FundraiserError,
+    instruction::InitializeIxData,
+};
+
+const USAGE: &str = "\
+Usage: fundraiser-cli <command> [options]
+
+Transactions are printed unsigned and base64 encoded, ready for an offline
+signer. --blockhash takes a recent blockhash or a durable nonce value and
+--fee-payer defaults to the first signer.
+
+Commands:
+  initialize  --maker <KEY> --mint <KEY> --amount <U64> --duration <DAYS>
+              [--receipt-rate <U64>] --blockhash <HASH> [--fee-payer <KEY>]
+              The fundraiser vault (its associated token account) must exist.
+  contribute  --contributor <KEY> --maker <KEY> --mint <KEY> --amount <U64>
+              [--tier <U8>] [--beneficiary <KEY>] [--round <KEY>]
+              [--allowlist-cap <U64>] [--proof <HEX>[,<HEX>...]]
+              --blockhash <HASH> [--fee-payer <KEY>]
+              --round is required while the fundraiser is in a round. Allowlisted
+              campaigns take the wallet cap (0 for uncapped) and the sibling
+              hashes of its merkle proof from the leaf up, 32 bytes in hex.
+  check       --maker <KEY> --mint <KEY> [--registry] --blockhash <HASH> [--fee-payer <KEY>]
+  refund      --contributor <KEY> --maker <KEY> --mint <KEY> [--rent-payer <KEY>]
+              --blockhash <HASH> [--fee-payer <KEY>]
+  decode      fundraiser|contributor <FILE> [--now <UNIX>]
+              FILE holds base64 account data or the JSON output of
+              `solana account --output json` or `getAccountInfo`
+  error       <CODE>
+              Explains a custom program error code, decimal or 0x prefixed hex
+  pda         fundraiser|receipt-mint|vault|metadata|registry --maker <KEY> [--mint <KEY>]
+  pda         contributor --maker <KEY> --owner <KEY>
+";
+
+type Result<T> = std::result::Result<T, String>;
+
+/// `--name value` options and bare `--flag` switches following the command
+struct Options {
+    values: HashMap<String, String>,
+    positional: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self> {
+        let mut values = HashMap::new();
+        let mut positional = Vec::new();
+        let mut args = args.iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) => {
+                    let value = match args.peek() {
+                        Some(value) if !value.starts_with("--") => args.next().unwrap().clone(),
+                        _ => String::new(),
+                    };
+                    values.insert(name.to_string(), value);
+                }
+                None => positional.push(arg.clone()),
+            }
+        }
+        Ok(Self { values, positional })
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    fn get<T: FromStr>(&self, name: &str) -> Result<Option<T>> {
+        self.values
+            .get(name)
+            .map(|value| value.parse().map_err(|_| format!("invalid value for --{name}: {value}")))
+            .transpose()
+    }
+
+    fn required<T: FromStr>(&self, name: &str) -> Result<T> {
+        self.get(name)?.ok_or_else(|| format!("missing --{name}"))
+    }
+
+    /// Comma separated 32 byte hex hashes, none for a bare switch
+    fn hashes(&self, name: &str) -> Result<Vec<[u8; 32]>> {
+        let Some(value) = self.values.get(name) else {
+            return Ok(Vec::new());
+        };
+        let invalid = || format!("invalid value for --{name}: {value}");
+        value
+            .split(',')
+            .filter(|hash| !hash.is_empty())
+            .map(|hash| {
+                if hash.len() != 64 || !hash.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+                    return Err(invalid());
+                }
+                let mut bytes = [0; 32];
+                for (index, byte) in bytes.iter_mut().enumerate() {
+                    *byte = u8::from_str_radix(&hash[2 * index..2 * index + 2], 16).unwrap();
+                }
+                Ok(bytes)
+            })
+            .collect()
+    }
+
+    fn positional(&self, index: usize, what: &str) -> Result<&str> {
+        self.positional
+            .get(index)
+            .map(String::as_str)
+            .ok_or_else(|| format!("missing {what}"))
+    }
+}
+
+/// Serializes `instruction` as an unsigned transaction and lists the signers
+fn print_transaction(instruction: Instruction, options: &Options) -> Result<()> {
+    let blockhash: Hash = options.required("blockhash")?;
+    let first_signer = instruction.accounts
+        .iter()
+        .find(|meta| meta.is_signer)
+        .map(|meta| meta.pubkey)
+        .ok_or("the instruction has no signer")?;
+    let fee_payer = options.get("fee-payer")?.unwrap_or(first_signer);
+
+    let message = Message::new_with_blockhash(&[instruction], Some(&fee_payer), &blockhash);
+    let signers = &message.account_keys[..message.header.num_required_signatures as usize];
+    for signer in signers {
+        eprintln!("signer: {signer}");
+    }
+    let transaction = Transaction::new_unsigned(message);
+    let bytes = bincode::serialize(&transaction).map_err(|err| err.to_string())?;
+    println!("{}", STANDARD.encode(bytes));
+    Ok(())
+}
+
+fn initialize(options: &Options) -> Result<Instruction> {
+    Ok(
+        (Initialize {
+            maker: options.required("maker")?,
+            mint_to_raise: options.required("mint")?,
+            params: InitializeIxData {
+                amount: options.required("amount")?,
+                duration: options.required("duration")?,
+                receipt_rate: options.get("receipt-rate")?.unwrap_or(RECEIPT_RATE_SCALER),
+                ..Default::default()
+            },
+            metadata: None,
+        }).instruction()
+    )
+}
+
+fn contribute(options: &Options) -> Result<Instruction> {
+    let mut contribute = Contribute::new(
+        options.required("contributor")?,
+        options.required("maker")?,
+        options.required("mint")?,
+        options.required("amount")?
+    );
+    contribute.tier = options.get("tier")?.unwrap_or(NO_TIER);
+    contribute.beneficiary = options.get("beneficiary")?;
+    contribute.round = options.get("round")?;
+    if options.flag("allowlist-cap") || options.flag("proof") {
+        contribute.allowlist = Some(AllowlistProof {
+            cap: options.get("allowlist-cap")?.unwrap_or(0),
+            proof: options.hashes("proof")?,
+        });
+    }
+    Ok(contribute.instruction())
+}
+
+fn check(options: &Options) -> Result<Instruction> {
+    Ok(
+        (CheckContribution {
+            maker: options.required("maker")?,
+            mint_to_raise: options.required("mint")?,
+            uses_registry: options.flag("registry"),
+        }).instruction()
+    )
+}
+
+fn refund(options: &Options) -> Result<Instruction> {
+    Ok(
+        (Refund {
+            contributor: options.required("contributor")?,
+            maker: options.required("maker")?,
+            mint_to_raise: options.required("mint")?,
+            rent_payer: options.get("rent-payer")?,
+        }).instruction()
+    )
+}
+
+/// Extracts the account data from a base64 dump or an RPC / CLI JSON dump
+fn read_account_data(path: &str) -> Result<Vec<u8>> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{path}: {err}"))?;
+    let contents = contents.trim();
+    let encoded = if contents.starts_with('{') {
+        let json: serde_json::Value = serde_json
+            ::from_str(contents)
+            .map_err(|err| format!("{path}: {err}"))?;
+        let data = [
+            json.pointer("/account/data"),
+            json.pointer("/result/value/data"),
+            json.pointer("/value/data"),
+            json.pointer("/data"),
+        ]
+            .into_iter()
+            .flatten()
+            .next()
+            .ok_or_else(|| format!("{path}: no account data found"))?;
+        // Encoded data is a `[data, encoding]` pair
+        if data[1] != "base64" {
+            return Err(format!("{path}: account data must be base64 encoded"));
+        }
+        data[0].as_str().unwrap_or_default().to_string()
+    } else {
+        contents.to_string()
+    };
+    STANDARD.decode(encoded).map_err(|err| format!("{path}: {err}"))
+}
+
+fn print_fundraiser(fundraiser: &FundraiserAccount, now: i64) {
+    let optional = |key: Option<Pubkey>| key.map_or("none".to_string(), |key| key.to_string());
+    println!("maker:             {}", fundraiser.maker);
+    println!("mint to raise:     {}", fundraiser.mint_to_raise);
+    println!("status:            {:?}", fundraiser.status(now));
+    println!(
+        "raised:            {} / {} ({}%)",
+        fundraiser.current_amount,
+        fundraiser.amount_to_raise,
+        fundraiser.progress_percent()
+    );
+    println!("matched:           {}", fundraiser.matched_amount);
+    println!("started:           {}", fundraiser.time_started);
+    println!("duration:          {} days", fundraiser.duration);
+    println!("time remaining:    {} s", fundraiser.time_remaining(now));
+    println!("max contribution:  {}", fundraiser.max_contribution());
+    println!("contributors:      {}", fundraiser.contributor_count);
+    println!("total weight:      {}", fundraiser.total_weight);
+    println!("receipt mint:      {}", optional(fundraiser.receipt_mint));
+    println!("receipt rate:      {}", fundraiser.receipt_rate);
+    println!("allowlisted:       {}", fundraiser.merkle_root.is_some());
+    println!("round:             {}", optional(fundraiser.round));
+    println!("registry:          {}", optional(fundraiser.registry));
+    println!("settled:           {}", fundraiser.settled);
+    println!("metadata locked:   {}", fundraiser.metadata_locked);
+    for (index, tier) in fundraiser.tiers.iter().enumerate() {
+        println!(
+            "tier {index}:            min {} backers {}/{} label {}",
+            tier.min_amount,
+            tier.backers,
+            tier.max_backers,
+            tier.label_id
+        );
+    }
+    for period in &fundraiser.bonus_periods {
+        println!("bonus:             +{} bps for {} s", period.bonus_bps, period.duration);
+    }
+}
+
+fn print_contributor(contributor: &ContributorAccount) {
+    println!("owner:             {}", contributor.owner);
+    println!("rent payer:        {}", contributor.rent_payer);
+    println!("amount:            {}", contributor.amount);
+    println!("weight:            {}", contributor.weight);
+    match contributor.tier {
+        Some(tier) => println!("tier:              {tier}"),
+        None => println!("tier:              none"),
+    }
+}
+
+fn decode(options: &Options) -> Result<()> {
+    let kind = options.positional(0, "account type")?;
+    let data = read_account_data(options.positional(1, "account file")?)?;
+    let now = match options.get("now")? {
+        Some(now) => now,
+        None =>
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_err(|err| err.to_string())?
+                .as_secs() as i64,
+    };
+    let error = |err| format!("cannot decode {kind} account: {err:?}");
+    match kind {
+        "fundraiser" => print_fundraiser(&FundraiserAccount::unpack(&data).map_err(error)?, now),
+        "contributor" => print_contributor(&ContributorAccount::unpack(&data).map_err(error)?),
+        other => {
+            return Err(format!("unknown account type {other}"));
+        }
+    }
+    Ok(())
+}
+
+fn explain_error(options: &Options) -> Result<()> {
+    let code = options.positional(0, "error code")?;
+    let parsed = match code.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => code.parse(),
+    };
+    let error = parsed
+        .ok()
+        .and_then(|code| FundraiserError::try_from(code).ok())
+        .ok_or_else(|| format!("unknown error code {code}"))?;
+    println!("{error:?} ({}): {error}", error as u32);
+    Ok(())
+}
+
+fn pda(options: &Options) -> Result<()> {
+    let maker: Pubkey = options.required("maker")?;
+    let (fundraiser, fundraiser_bump) = find_fundraiser_address(&maker);
+    let (address, bump) = match options.positional(0, "address kind")? {
+        "fundraiser" => (fundraiser, Some(fundraiser_bump)),
+        "receipt-mint" => {
+            let (address, bump) = find_receipt_mint_address(&fundraiser);
+            (address, Some(bump))
+        }
+        "vault" => (get_associated_token_address(&fundraiser, &options.required("mint")?), None),
+        "metadata" => {
+            let (address, bump) = find_metadata_address(&fundraiser);
+            (address, Some(bump))
+        }
+        "registry" => {
+            let (address, bump) = find_registry_address(&fundraiser);
+            (address, Some(bump))
+        }
+        "contributor" => {
+            let (address, bump) = find_contributor_address(
+                &fundraiser,
+                &options.required("owner")?
+            );
+            (address, Some(bump))
+        }
+        other => {
+            return Err(format!("unknown address kind {other}"));
+        }
+    };
+    match bump {
+        Some(bump) => println!("{address} (bump {bump})"),
+        None => println!("{address}"),
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let Some((command, args)) = args.split_first() else {
+        return Err(USAGE.to_string());
+    };
+    let options = Options::parse(args)?;
+    match command.as_str() {
+        "initialize" => print_transaction(initialize(&options)?, &options),
+        "contribute" => print_transaction(contribute(&options)?, &options),
+        "check" => print_transaction(check(&options)?, &options),
+        "refund" => print_transaction(refund(&options)?, &options),
+        "decode" => decode(&options),
+        "error" => explain_error(&options),
+        "pda" => pda(&options),
+        "help" | "--help" | "-h" => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        other => Err(format!("unknown command {other}\n\n{USAGE}")),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::vec::Vec;
+
+use pinocchio::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+pub use crate::state::CampaignStatus;
+
+use crate::{
+    constants::{
+        MAX_BONUS_PERIODS,
+        MAX_CONTRIBUTION_PERCENTAGE,
+        MAX_TIERS,
+        NO_TIER,
+        PERCENTAGE_SCALER,
+        SECONDS_TO_DAYS,
+    },
+    state::{ Contributor, Fundraiser },
+    utils::{ check_account_tag, DataLen, Discriminator },
+    wire::WireReader,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TierInfo {
+    pub min_amount: u64,
+    // 0 means the tier has no backer cap
+    pub max_backers: u32,
+    pub backers: u32,
+    pub label_id: u16,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BonusPeriodInfo {
+    pub duration: i64,
+    pub bonus_bps: u16,
+}
+
+/// Owned copy of a `Fundraiser` account. Unused tiers and bonus periods are
+/// left out and all-zero keys are `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FundraiserAccount {
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount_to_raise: u64,
+    pub current_amount: u64,
+    pub time_started: i64,
+    pub duration: u8,
+    pub bump: u8,
+    pub tiers: Vec<TierInfo>,
+    pub receipt_mint: Option<Pubkey>,
+    pub receipt_rate: u64,
+    pub receipt_bump: u8,
+    pub merkle_root: Option<[u8; 32]>,
+    pub bonus_periods: Vec<BonusPeriodInfo>,
+    pub total_weight: u64,
+    pub round: Option<Pubkey>,
+    pub matched_amount: u64,
+    pub settled: bool,
+    pub contributor_count: u32,
+    pub registry: Option<Pubkey>,
+    pub metadata_locked: bool,
+}
+
+/// Owned copy of a `Contributor` account
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContributorAccount {
+    pub owner: Pubkey,
+    pub rent_payer: Pubkey,
+    pub amount: u64,
+    // Index into `FundraiserAccount::tiers`, `None` if no tier was selected
+    pub tier: Option<u8>,
+    pub weight: u64,
+}
+
+/// Reads a key, mapping the all-zero value used for unset keys to `None`
+fn read_optional_key(reader: &mut WireReader) -> Result<Option<Pubkey>, ProgramError> {
+    let key = reader.read_bytes::<32>()?;
+    Ok((key != [0; 32]).then(|| Pubkey::new_from_array(key)))
+}
+
+/// Checks the length and leading tag of `data` and returns a reader past them
+fn account_reader<T: DataLen + Discriminator>(
+    data: &[u8]
+) -> Result<WireReader<'_>, ProgramError> {
+    if data.len() != T::LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    check_account_tag::<T>(data[0], data[1])?;
+    WireReader::new(&data[2..], T::LEN - 2)
+}
+
+impl FundraiserAccount {
+    /// Decodes the data of a `Fundraiser` account of the current layout
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = account_reader::<Fundraiser>(data)?;
+        let maker = Pubkey::new_from_array(reader.read_bytes()?);
+        let mint_to_raise = Pubkey::new_from_array(reader.read_bytes()?);
+        let amount_to_raise = reader.read_u64()?;
+        let current_amount = reader.read_u64()?;
+        let time_started = reader.read_i64()?;
+        let duration = reader.read_u8()?;
+        let bump = reader.read_u8()?;
+        let tier_count = reader.read_u8()? as usize;
+        let mut tiers = Vec::with_capacity(MAX_TIERS);
+        for _ in 0..MAX_TIERS {
+            tiers.push(TierInfo {
+                min_amount: reader.read_u64()?,
+                max_backers: reader.read_u32()?,
+                backers: reader.read_u32()?,
+                label_id: reader.read_u16()?,
+            });
+        }
+        tiers.truncate(tier_count);
+        let receipt_mint = read_optional_key(&mut reader)?;
+        let receipt_rate = reader.read_u64()?;
+        let receipt_bump = reader.read_u8()?;
+        let merkle_root = reader.read_bytes::<32>()?;
+        let mut bonus_periods = Vec::with_capacity(MAX_BONUS_PERIODS);
+        for _ in 0..MAX_BONUS_PERIODS {
+            let period = BonusPeriodInfo {
+                duration: reader.read_i64()?,
+                bonus_bps: reader.read_u16()?,
+            };
+            // Unused periods are always at the end of the schedule
+            if period.duration != 0 {
+                bonus_periods.push(period);
+            }
+        }
+
+        Ok(Self {
+            maker,
+            mint_to_raise,
+            amount_to_raise,
+            current_amount,
+            time_started,
+            duration,
+            bump,
+            tiers,
+            receipt_mint,
+            receipt_rate,
+            receipt_bump,
+            merkle_root: (merkle_root != [0; 32]).then_some(merkle_root),
+            bonus_periods,
+            total_weight: reader.read_u64()?,
+            round: read_optional_key(&mut reader)?,
+            matched_amount: reader.read_u64()?,
+            settled: reader.read_u8()? != 0,
+            contributor_count: reader.read_u32()?,
+            registry: read_optional_key(&mut reader)?,
+            metadata_locked: reader.read_u8()? != 0,
+        })
+    }
+
+    /// Whole days elapsed at `now`, same as `Fundraiser::elapsed_days`
+    pub fn elapsed_days(&self, now: i64) -> i64 {
+        (now - self.time_started) / SECONDS_TO_DAYS
+    }
+
+    /// Same rule as `Fundraiser::is_open`, checked by contributions
+    pub fn is_open(&self, now: i64) -> bool {
+        self.elapsed_days(now) <= (self.duration as i64)
+    }
+
+    /// Same rule as `Fundraiser::is_ended`
+    pub fn is_ended(&self, now: i64) -> bool {
+        !self.is_open(now)
+    }
+
+    /// Same rule as `Fundraiser::is_refund_window`, checked by refunds
+    pub fn is_refund_window(&self, now: i64) -> bool {
+        self.elapsed_days(now) >= (self.duration as i64)
+    }
+
+    pub fn is_target_met(&self) -> bool {
+        self.current_amount >= self.amount_to_raise
+    }
+
+    /// Same rule as `Fundraiser::status`
+    pub fn status(&self, now: i64) -> CampaignStatus {
+        if self.settled {
+            CampaignStatus::Finalized
+        } else if now < self.time_started {
+            CampaignStatus::NotStarted
+        } else if self.is_target_met() {
+            // The maker can claim as soon as the target is met
+            CampaignStatus::Succeeded
+        } else if self.is_open(now) {
+            CampaignStatus::Active
+        } else {
+            CampaignStatus::Failed
+        }
+    }
+
+    /// Raised share of the target in whole percent, above 100 when overfunded
+    pub fn progress_percent(&self) -> u64 {
+        if self.amount_to_raise == 0 {
+            return 0;
+        }
+        (((self.current_amount as u128) * (PERCENTAGE_SCALER as u128)) /
+            (self.amount_to_raise as u128)) as u64
+    }
+
+    /// Seconds left to contribute at `now`, 0 once contributions closed.
+    /// Contributions are accepted through the whole last day.
+    pub fn time_remaining(&self, now: i64) -> i64 {
+        let closes_at = self.time_started + ((self.duration as i64) + 1) * SECONDS_TO_DAYS;
+        (closes_at - now).max(0)
+    }
+
+    /// Largest single contribution, same as `Fundraiser::max_contribution`
+    pub fn max_contribution(&self) -> u64 {
+        (self.amount_to_raise * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER
+    }
+
+    /// Largest amount a wallet that already contributed `contributed` can
+    /// add at `now`. Allowlist caps and tier minimums are not included.
+    pub fn remaining_allowance(&self, contributed: u64, now: i64) -> u64 {
+        if self.settled || !self.is_open(now) {
+            return 0;
+        }
+        let max_contribution = self.max_contribution();
+        // Wallets at or under the cap may still add one full contribution
+        if contributed > max_contribution {
+            0
+        } else {
+            max_contribution
+        }
+    }
+}
+
+impl ContributorAccount {
+    /// Decodes the data of a `Contributor` account of the current layout
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = account_reader::<Contributor>(data)?;
+        Ok(Self {
+            owner: Pubkey::new_from_array(reader.read_bytes()?),
+            rent_payer: Pubkey::new_from_array(reader.read_bytes()?),
+            amount: reader.read_u64()?,
+            tier: Some(reader.read_u8()?).filter(|tier| *tier != NO_TIER),
+            weight: reader.read_u64()?,
+        })
+    }
+}
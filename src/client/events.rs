@@ -0,0 +1,54 @@
+use std::{ string::ToString, vec::Vec };
+
+use base64::{ engine::general_purpose::STANDARD, Engine };
+use solana_program::pubkey::Pubkey;
+
+use crate::{ client::PROGRAM_ID, event::FundraiserEvent };
+
+/// Decodes the events logged by the program in the logs of a transaction,
+/// in emission order. `Program data:` lines of other programs, including
+/// the ones we invoke, are skipped by tracking the invocation stack.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<FundraiserEvent> {
+    let program_id = PROGRAM_ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() == Some(&program_id.as_str()) {
+                events.extend(parse_log_data(data));
+            }
+        } else if let Some((program, invoked)) = parse_invocation(line) {
+            if invoked {
+                stack.push(program);
+            } else {
+                stack.pop();
+            }
+        }
+    }
+    events
+}
+
+/// Reads the runtime's `Program <pubkey> invoke [<depth>]` and
+/// `Program <pubkey> success` / `Program <pubkey> failed: <error>` lines, as
+/// `(program, true)` on entry and `(program, false)` on exit. Anything else,
+/// including program logs that happen to start the same way, is `None`.
+fn parse_invocation(line: &str) -> Option<(&str, bool)> {
+    let (program, action) = line.strip_prefix("Program ")?.split_once(' ')?;
+    program.parse::<Pubkey>().ok()?;
+    if let Some(depth) = action.strip_prefix("invoke [").and_then(|rest| rest.strip_suffix(']')) {
+        let is_depth = !depth.is_empty() && depth.bytes().all(|byte| byte.is_ascii_digit());
+        return is_depth.then_some((program, true));
+    }
+    (action == "success" || action.starts_with("failed: ")).then_some((program, false))
+}
+
+/// Decodes the base64 fields of one `Program data:` line. The program logs
+/// each event as a single field.
+pub fn parse_log_data(data: &str) -> Option<FundraiserEvent> {
+    let mut fields = data.split(' ');
+    let event = STANDARD.decode(fields.next()?).ok()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    FundraiserEvent::unpack(&event).ok()
+}
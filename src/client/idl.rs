@@ -0,0 +1,691 @@
+//! JSON interface description in the Anchor IDL shape. Instruction data and
+//! events are packed little-endian, which matches Borsh for the fixed-size
+//! fields used here. Account state is cast in place, so state types carry a C
+//! representation and zero-copy (bytemuck) serialization. The generated
+//! description is checked in at `idl/pinocchio_fundraiser.json` and kept in
+//! sync with the processors by the unit tests.
+
+use std::{ format, string::{ String, ToString }, vec, vec::Vec };
+
+use serde_json::{ json, Value };
+
+use crate::{
+    client::{ PROGRAM_ID, TOKEN_PROGRAM_ID },
+    constants::{
+        MAX_BONUS_PERIODS,
+        MAX_NAME_LEN,
+        MAX_ROUND_CAMPAIGNS,
+        MAX_SYMBOL_LEN,
+        MAX_TIERS,
+        MAX_URI_LEN,
+    },
+    error::FundraiserError,
+    event::EventDiscriminator,
+    instruction::ProgramInstruction,
+    state::{ AccountDiscriminator, CampaignStatus },
+};
+
+const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+
+fn account(name: &str, writable: bool, signer: bool) -> Value {
+    json!({ "name": name, "writable": writable, "signer": signer })
+}
+
+/// Trailing account the processor only reads when `docs` applies
+fn optional(mut account: Value, docs: &str) -> Value {
+    account["optional"] = json!(true);
+    account["docs"] = json!([docs]);
+    account
+}
+
+fn system_program() -> Value {
+    json!({ "name": "system_program", "address": SYSTEM_PROGRAM_ID })
+}
+
+fn token_program() -> Value {
+    json!({ "name": "token_program", "address": TOKEN_PROGRAM_ID.to_string() })
+}
+
+fn field(name: &str, ty: Value) -> Value {
+    json!({ "name": name, "type": ty })
+}
+
+fn array(ty: Value, len: usize) -> Value {
+    json!({ "array": [ty, len] })
+}
+
+fn bytes(len: usize) -> Value {
+    array(json!("u8"), len)
+}
+
+fn defined(name: &str) -> Value {
+    json!({ "defined": { "name": name } })
+}
+
+fn instruction(
+    name: &str,
+    discriminator: ProgramInstruction,
+    docs: &[&str],
+    accounts: Vec<Value>,
+    args: Vec<Value>
+) -> Value {
+    json!({
+        "name": name,
+        "docs": docs,
+        "discriminator": [discriminator as u8],
+        "accounts": accounts,
+        "args": args,
+    })
+}
+
+/// Struct type cast from account data. `packed` layouts are made of
+/// alignment-1 fields and have no padding.
+fn state_type(name: &str, docs: &[&str], packed: bool, fields: Vec<Value>) -> Value {
+    let repr = if packed { json!({ "kind": "c", "packed": true }) } else { json!({ "kind": "c" }) };
+    json!({
+        "name": name,
+        "docs": docs,
+        "serialization": "bytemuck",
+        "repr": repr,
+        "type": { "kind": "struct", "fields": fields },
+    })
+}
+
+/// Struct type encoded in instruction data
+fn args_type(name: &str, fields: Vec<Value>) -> Value {
+    json!({ "name": name, "type": { "kind": "struct", "fields": fields } })
+}
+
+fn instructions() -> Vec<Value> {
+    vec![
+        instruction(
+            "initialize",
+            ProgramInstruction::Initialize,
+            &[
+                "Creates the fundraiser PDA and its receipt mint.",
+                "`MetadataArgs` may follow the arguments, with the metadata account passed.",
+            ],
+            vec![
+                account("maker", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("vault", false, false),
+                account("receipt_mint", true, false),
+                system_program(),
+                token_program(),
+                optional(account("metadata", true, false), "Created when metadata is appended")
+            ],
+            vec![
+                field("amount", json!("u64")),
+                field("duration", json!("u8")),
+                field("bump", json!("u8")),
+                field("tier_count", json!("u8")),
+                field("tiers", array(defined("TierArgs"), MAX_TIERS)),
+                field("receipt_rate", json!("u64")),
+                field("receipt_bump", json!("u8")),
+                field("merkle_root", bytes(32)),
+                field("bonus_periods", array(defined("BonusPeriod"), MAX_BONUS_PERIODS))
+            ]
+        ),
+        instruction(
+            "contribute",
+            ProgramInstruction::Contribute,
+            &[
+                "Moves tokens into the vault and credits them to the beneficiary.",
+                "Allowlisted campaigns append the `AllowlistProof` of the beneficiary.",
+            ],
+            vec![
+                account("contributor", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("contributor_acc", true, false),
+                account("contributor_ata", true, false),
+                account("vault", true, false),
+                token_program(),
+                system_program(),
+                account("receipt_mint", true, false),
+                account("contributor_receipt_ata", true, false),
+                optional(account("round", true, false), "Required while in a round"),
+                optional(
+                    account("beneficiary", false, true),
+                    "Required when crediting another wallet"
+                ),
+                optional(account("fee_payer", true, true), "Pays the contributor account rent")
+            ],
+            vec![
+                field("amount", json!("u64")),
+                field("fundraiser_bump", json!("u8")),
+                field("contributor_bump", json!("u8")),
+                field("tier", json!("u8")),
+                field("beneficiary", json!("pubkey"))
+            ]
+        ),
+        instruction(
+            "check_contribution",
+            ProgramInstruction::CheckContribution,
+            &["Transfers the raised funds of a successful fundraiser to the maker."],
+            vec![
+                account("maker", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("vault", true, false),
+                account("maker_ata", true, false),
+                token_program(),
+                system_program(),
+                optional(account("registry", true, false), "Closed for registry fundraisers")
+            ],
+            vec![]
+        ),
+        instruction(
+            "refund",
+            ProgramInstruction::Refund,
+            &["Returns a contribution to a fundraiser that missed its target."],
+            vec![
+                account("contributor", true, true),
+                account("maker", false, false),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("contributor_acc", true, false),
+                account("contributor_ata", true, false),
+                account("vault", true, false),
+                system_program(),
+                token_program(),
+                account("receipt_mint", true, false),
+                account("contributor_receipt_ata", true, false),
+                optional(account("rent_payer", true, false), "Fee payer that funded the account")
+            ],
+            vec![field("contributor_bump", json!("u8"))]
+        ),
+        instruction(
+            "update_metadata",
+            ProgramInstruction::UpdateMetadata,
+            &["Replaces the fundraiser metadata before the first contribution."],
+            vec![
+                account("maker", false, true),
+                account("fundraiser", false, false),
+                account("metadata", true, false)
+            ],
+            vec![field("metadata", defined("MetadataArgs"))]
+        ),
+        instruction(
+            "initialize_round",
+            ProgramInstruction::InitializeRound,
+            &["Creates a quadratic funding round with its matching pool vault."],
+            vec![
+                account("authority", true, true),
+                account("round", true, false),
+                account("mint", false, false),
+                account("vault", false, false),
+                system_program()
+            ],
+            vec![
+                field("id", json!("u64")),
+                field("end_time", json!("i64")),
+                field("bump", json!("u8"))
+            ]
+        ),
+        instruction(
+            "join_round",
+            ProgramInstruction::JoinRound,
+            &["Adds a fundraiser to a round, signed by the maker and the round authority."],
+            vec![
+                account("maker", false, true),
+                account("fundraiser", true, false),
+                account("round", true, false),
+                account("authority", false, true)
+            ],
+            vec![]
+        ),
+        instruction(
+            "distribute_round",
+            ProgramInstruction::DistributeRound,
+            &["Pays the match of one campaign of an ended round to its maker."],
+            vec![
+                account("round", true, false),
+                account("vault", true, false),
+                account("mint", false, false),
+                account("maker_ata", true, false),
+                token_program()
+            ],
+            vec![field("index", json!("u8"))]
+        ),
+        instruction(
+            "create_match_pledge",
+            ProgramInstruction::CreateMatchPledge,
+            &["Escrows sponsor tokens matching contributions up to a cap."],
+            vec![
+                account("sponsor", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", false, false),
+                account("pledge", true, false),
+                account("pledge_vault", true, false),
+                account("sponsor_ata", true, false),
+                system_program(),
+                token_program()
+            ],
+            vec![field("cap", json!("u64")), field("bump", json!("u8"))]
+        ),
+        instruction(
+            "settle_match",
+            ProgramInstruction::SettleMatch,
+            &["Moves the matched share of new contributions into the fundraiser vault."],
+            vec![
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("pledge", true, false),
+                account("pledge_vault", true, false),
+                account("vault", true, false),
+                token_program()
+            ],
+            vec![]
+        ),
+        instruction(
+            "release_match",
+            ProgramInstruction::ReleaseMatch,
+            &["Returns the unused escrow to the sponsor and closes the pledge."],
+            vec![
+                account("sponsor", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("pledge", true, false),
+                account("pledge_vault", true, false),
+                account("vault", true, false),
+                account("sponsor_ata", true, false),
+                token_program()
+            ],
+            vec![]
+        ),
+        instruction(
+            "transfer_position",
+            ProgramInstruction::TransferPosition,
+            &[
+                "Moves part of a contribution and its receipts to another wallet.",
+                "Allowlisted campaigns append the `AllowlistProof` of the recipient.",
+            ],
+            vec![
+                account("owner", true, true),
+                account("fundraiser", true, false),
+                account("from_contributor_acc", true, false),
+                account("to_contributor_acc", true, false),
+                account("receipt_mint", false, false),
+                account("from_receipt_ata", true, false),
+                account("to_receipt_ata", true, false),
+                system_program(),
+                token_program(),
+                optional(account("rent_payer", true, false), "Fee payer that funded the account")
+            ],
+            vec![
+                field("amount", json!("u64")),
+                field("recipient", json!("pubkey")),
+                field("recipient_bump", json!("u8")),
+                field("owner_bump", json!("u8"))
+            ]
+        ),
+        instruction(
+            "close_contributor",
+            ProgramInstruction::CloseContributor,
+            &["Closes a contributor account once the raised funds are claimed."],
+            vec![
+                account("contributor", true, true),
+                account("maker", true, false),
+                account("fundraiser", true, false),
+                account("contributor_acc", true, false),
+                optional(account("rent_payer", true, false), "Fee payer that funded the account")
+            ],
+            vec![field("contributor_bump", json!("u8"))]
+        ),
+        instruction(
+            "initialize_registry",
+            ProgramInstruction::InitializeRegistry,
+            &[
+                "Switches a fundraiser to the compact contributor registry.",
+                "The maker prepays the rent of `capacity` entries.",
+            ],
+            vec![
+                account("maker", true, true),
+                account("fundraiser", true, false),
+                account("registry", true, false),
+                system_program()
+            ],
+            vec![field("bump", json!("u8")), field("capacity", json!("u32"))]
+        ),
+        instruction(
+            "contribute_registry",
+            ProgramInstruction::ContributeRegistry,
+            &[
+                "Contributes to a registry fundraiser.",
+                "Allowlisted campaigns append the `AllowlistProof` of the contributor.",
+            ],
+            vec![
+                account("contributor", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("registry", true, false),
+                account("contributor_ata", true, false),
+                account("vault", true, false),
+                token_program(),
+                system_program(),
+                account("receipt_mint", true, false),
+                account("contributor_receipt_ata", true, false)
+            ],
+            vec![field("amount", json!("u64"))]
+        ),
+        instruction(
+            "refund_registry",
+            ProgramInstruction::RefundRegistry,
+            &["Refunds a registry contributor of a fundraiser that missed its target."],
+            vec![
+                account("contributor", true, true),
+                account("mint_to_raise", false, false),
+                account("fundraiser", true, false),
+                account("registry", true, false),
+                account("contributor_ata", true, false),
+                account("vault", true, false),
+                token_program(),
+                account("receipt_mint", true, false),
+                account("contributor_receipt_ata", true, false)
+            ],
+            vec![]
+        ),
+        instruction(
+            "migrate",
+            ProgramInstruction::Migrate,
+            &["Rewrites an account from an earlier layout into the current one."],
+            vec![
+                account("payer", true, true),
+                account("account", true, false),
+                system_program(),
+                optional(
+                    account("fundraiser", true, false),
+                    "Fundraiser of a first layout contributor"
+                ),
+                optional(account("owner", false, false), "Wallet of a first layout contributor")
+            ],
+            vec![field("bump", json!("u8"))]
+        ),
+        {
+            let mut get_status = instruction(
+                "get_status",
+                ProgramInstruction::GetStatus,
+                &[
+                    "Writes the fundraiser status as return data, read-only.",
+                    "The contribution of the contributor account is included when passed.",
+                ],
+                vec![
+                    account("fundraiser", false, false),
+                    optional(
+                        account("contributor_acc", false, false),
+                        "Contributor account to report the contribution of"
+                    )
+                ],
+                vec![field("contributor_bump", json!("u8"))]
+            );
+            get_status["returns"] = defined("FundraiserStatus");
+            get_status
+        }
+    ]
+}
+
+fn accounts() -> Vec<Value> {
+    [
+        ("Fundraiser", AccountDiscriminator::Fundraiser),
+        ("Contributor", AccountDiscriminator::Contributor),
+        ("FundraiserMetadata", AccountDiscriminator::Metadata),
+        ("Round", AccountDiscriminator::Round),
+        ("MatchPledge", AccountDiscriminator::MatchPledge),
+        ("ContributorRegistry", AccountDiscriminator::Registry),
+    ]
+        .into_iter()
+        .map(|(name, discriminator)| {
+            json!({ "name": name, "discriminator": [discriminator as u8] })
+        })
+        .collect()
+}
+
+fn types() -> Vec<Value> {
+    vec![
+        state_type(
+            "Fundraiser",
+            &["Fundraiser state, seeds `[\"fundraiser\", maker]`."],
+            true,
+            vec![
+                field("version", json!("u8")),
+                field("maker", json!("pubkey")),
+                field("mint_to_raise", json!("pubkey")),
+                field("amount_to_raise", json!("u64")),
+                field("current_amount", json!("u64")),
+                field("time_started", json!("i64")),
+                field("duration", json!("u8")),
+                field("bump", json!("u8")),
+                field("tier_count", json!("u8")),
+                field("tiers", array(defined("Tier"), MAX_TIERS)),
+                field("receipt_mint", json!("pubkey")),
+                field("receipt_rate", json!("u64")),
+                field("receipt_bump", json!("u8")),
+                field("merkle_root", bytes(32)),
+                field("bonus_periods", array(defined("BonusPeriod"), MAX_BONUS_PERIODS)),
+                field("total_weight", json!("u64")),
+                field("round", json!("pubkey")),
+                field("matched_amount", json!("u64")),
+                field("settled", json!("bool")),
+                field("contributor_count", json!("u32")),
+                field("registry", json!("pubkey")),
+                field("metadata_locked", json!("bool"))
+            ]
+        ),
+        state_type("Tier", &[], true, vec![
+            field("min_amount", json!("u64")),
+            field("max_backers", json!("u32")),
+            field("backers", json!("u32")),
+            field("label_id", json!("u16"))
+        ]),
+        state_type("BonusPeriod", &[], true, vec![
+            field("duration", json!("i64")),
+            field("bonus_bps", json!("u16"))
+        ]),
+        state_type(
+            "Contributor",
+            &["Contributor state, seeds `[\"contributor\", fundraiser, owner]`."],
+            true,
+            vec![
+                field("version", json!("u8")),
+                field("owner", json!("pubkey")),
+                field("rent_payer", json!("pubkey")),
+                field("amount", json!("u64")),
+                field("tier", json!("u8")),
+                field("weight", json!("u64"))
+            ]
+        ),
+        state_type(
+            "FundraiserMetadata",
+            &["Fundraiser metadata, seeds `[\"metadata\", fundraiser]`."],
+            false,
+            vec![
+                field("version", json!("u8")),
+                field("fundraiser", json!("pubkey")),
+                field("name", bytes(MAX_NAME_LEN)),
+                field("symbol", bytes(MAX_SYMBOL_LEN)),
+                field("uri", bytes(MAX_URI_LEN)),
+                field("content_hash", bytes(32)),
+                field("category", json!("u8")),
+                field("bump", json!("u8"))
+            ]
+        ),
+        state_type(
+            "Round",
+            &["Quadratic funding round, seeds `[\"round\", authority, id]`."],
+            true,
+            vec![
+                field("version", json!("u8")),
+                field("authority", json!("pubkey")),
+                field("mint", json!("pubkey")),
+                field("vault", json!("pubkey")),
+                field("id", json!("u64")),
+                field("end_time", json!("i64")),
+                field("matching_pool", json!("u64")),
+                field("entry_count", json!("u8")),
+                field("bump", json!("u8")),
+                field("entries", array(defined("RoundEntry"), MAX_ROUND_CAMPAIGNS))
+            ]
+        ),
+        state_type("RoundEntry", &[], true, vec![
+            field("fundraiser", json!("pubkey")),
+            field("maker", json!("pubkey")),
+            field("amount_to_raise", json!("u64")),
+            field("current_amount", json!("u64")),
+            field("sum_sqrt", json!("u64")),
+            field("paid", json!("bool"))
+        ]),
+        state_type(
+            "MatchPledge",
+            &["Sponsor match pledge, seeds `[\"match\", fundraiser, sponsor]`."],
+            true,
+            vec![
+                field("version", json!("u8")),
+                field("sponsor", json!("pubkey")),
+                field("fundraiser", json!("pubkey")),
+                field("vault", json!("pubkey")),
+                field("cap", json!("u64")),
+                field("matched", json!("u64")),
+                field("bump", json!("u8"))
+            ]
+        ),
+        state_type(
+            "ContributorRegistry",
+            &[
+                "Registry header, seeds `[\"registry\", fundraiser]`.",
+                "`entry_count` `RegistryEntry` values sorted by contributor follow the header.",
+            ],
+            true,
+            vec![
+                field("version", json!("u8")),
+                field("bump", json!("u8")),
+                field("fundraiser", json!("pubkey")),
+                field("entry_count", json!("u64"))
+            ]
+        ),
+        state_type("RegistryEntry", &[], true, vec![
+            field("contributor", json!("pubkey")),
+            field("amount", json!("u64"))
+        ]),
+        args_type("TierArgs", vec![
+            field("min_amount", json!("u64")),
+            field("max_backers", json!("u32")),
+            field("label_id", json!("u16"))
+        ]),
+        args_type("MetadataArgs", vec![
+            field("name", bytes(MAX_NAME_LEN)),
+            field("symbol", bytes(MAX_SYMBOL_LEN)),
+            field("uri", bytes(MAX_URI_LEN)),
+            field("content_hash", bytes(32)),
+            field("category", json!("u8")),
+            field("bump", json!("u8"))
+        ]),
+        args_type("AllowlistProof", vec![
+            field("cap", json!("u64")),
+            field("proof", json!({ "vec": bytes(32) }))
+        ]),
+        json!({
+            "name": "CampaignStatus",
+            "type": {
+                "kind": "enum",
+                "variants": (0..)
+                    .map_while(|code| CampaignStatus::try_from(code).ok())
+                    .map(|status| json!({ "name": format!("{status:?}") }))
+                    .collect::<Vec<_>>(),
+            },
+        }),
+        args_type("FundraiserStatus", vec![
+            field("status", defined("CampaignStatus")),
+            field("amount_to_raise", json!("u64")),
+            field("current_amount", json!("u64")),
+            field("time_started", json!("i64")),
+            field("duration", json!("u8")),
+            field("contributor_count", json!("u32")),
+            field("contributed", json!("u64")),
+            field("weight", json!("u64"))
+        ]),
+        args_type("FundraiserCreated", vec![
+            field("fundraiser", json!("pubkey")),
+            field("maker", json!("pubkey")),
+            field("mint_to_raise", json!("pubkey")),
+            field("amount_to_raise", json!("u64")),
+            field("time_started", json!("i64")),
+            field("duration", json!("u8"))
+        ]),
+        args_type("Contributed", vec![
+            field("fundraiser", json!("pubkey")),
+            field("contributor", json!("pubkey")),
+            field("amount", json!("u64")),
+            field("current_amount", json!("u64"))
+        ]),
+        args_type("Refunded", vec![
+            field("fundraiser", json!("pubkey")),
+            field("contributor", json!("pubkey")),
+            field("amount", json!("u64"))
+        ]),
+        args_type("Finalized", vec![
+            field("fundraiser", json!("pubkey")),
+            field("maker", json!("pubkey")),
+            field("amount", json!("u64"))
+        ]),
+        args_type("Cancelled", vec![field("fundraiser", json!("pubkey"))]),
+        args_type("Matched", vec![
+            field("fundraiser", json!("pubkey")),
+            field("sponsor", json!("pubkey")),
+            field("amount", json!("u64")),
+            field("current_amount", json!("u64"))
+        ])
+    ]
+}
+
+/// Events logged with `sol_log_data`, the discriminator prefixes the fields
+fn events() -> Vec<Value> {
+    [
+        ("FundraiserCreated", EventDiscriminator::FundraiserCreated),
+        ("Contributed", EventDiscriminator::Contributed),
+        ("Refunded", EventDiscriminator::Refunded),
+        ("Finalized", EventDiscriminator::Finalized),
+        ("Cancelled", EventDiscriminator::Cancelled),
+        ("Matched", EventDiscriminator::Matched),
+    ]
+        .into_iter()
+        .map(|(name, discriminator)| {
+            json!({ "name": name, "discriminator": [discriminator as u8] })
+        })
+        .collect()
+}
+
+fn errors() -> Vec<Value> {
+    // Codes are dense, the first unknown code ends the list
+    (0..)
+        .map_while(|code| FundraiserError::try_from(code).ok())
+        .map(|error| {
+            json!({ "code": error as u32, "name": format!("{error:?}"), "msg": error.message() })
+        })
+        .collect()
+}
+
+/// Interface description of the program
+pub fn idl() -> Value {
+    json!({
+        "address": PROGRAM_ID.to_string(),
+        "metadata": {
+            "name": "pinocchio_fundraiser",
+            "version": env!("CARGO_PKG_VERSION"),
+            "spec": "0.1.0",
+        },
+        "instructions": instructions(),
+        "accounts": accounts(),
+        "events": events(),
+        "types": types(),
+        "errors": errors(),
+    })
+}
+
+/// The description as written to `idl/pinocchio_fundraiser.json`
+pub fn idl_json() -> String {
+    let mut json = serde_json::to_string_pretty(&idl()).unwrap();
+    json.push('\n');
+    json
+}
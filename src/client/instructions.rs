@@ -0,0 +1,649 @@
+use std::{ vec, vec::Vec };
+
+use solana_program::{
+    instruction::{ AccountMeta, Instruction },
+    pubkey::Pubkey,
+    system_program,
+};
+
+use crate::{
+    client::*,
+    constants::NO_TIER,
+    instruction::{
+        CloseContributorIxData,
+        ContributeIxData,
+        ContributeRegistryIxData,
+        CreateMatchPledgeIxData,
+        DistributeRoundIxData,
+        GetStatusIxData,
+        InitializeIxData,
+        InitializeRegistryIxData,
+        InitializeRoundIxData,
+        MetadataIxData,
+        MigrateIxData,
+        ProgramInstruction,
+        RefundIxData,
+        TransferPositionIxData,
+    },
+    wire::IxData,
+};
+
+/// Prefixes the instruction discriminator to the encoded data
+fn build(instruction: ProgramInstruction, accounts: Vec<AccountMeta>, data: &[u8]) -> Instruction {
+    let mut ix_data = Vec::with_capacity(1 + data.len());
+    ix_data.push(instruction as u8);
+    ix_data.extend_from_slice(data);
+    Instruction { program_id: PROGRAM_ID, accounts, data: ix_data }
+}
+
+/// Allowlist cap and merkle proof of a wallet, appended to the data of
+/// instructions crediting allowlisted campaigns
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AllowlistProof {
+    // Contribution cap of the wallet, 0 for uncapped
+    pub cap: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl AllowlistProof {
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(8 + 32 * self.proof.len());
+        data.extend_from_slice(&self.cap.to_le_bytes());
+        self.proof.iter().for_each(|node| data.extend_from_slice(node));
+        data
+    }
+}
+
+/// Creates the fundraiser of `maker` and its receipt mint. The vault, the
+/// fundraiser associated token account, must exist beforehand.
+#[derive(Clone, Debug)]
+pub struct Initialize {
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    // Campaign parameters, the fundraiser and receipt mint bumps are derived
+    pub params: InitializeIxData,
+    // Optional metadata, its bump is derived
+    pub metadata: Option<MetadataIxData>,
+}
+
+impl Initialize {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, bump) = find_fundraiser_address(&self.maker);
+        let (receipt_mint, receipt_bump) = find_receipt_mint_address(&fundraiser);
+        let mut accounts = vec![
+            AccountMeta::new(self.maker, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new_readonly(
+                get_associated_token_address(&fundraiser, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+
+        let params = InitializeIxData { bump, receipt_bump, ..self.params.clone() };
+        let mut data = params.pack();
+        if let Some(metadata) = &self.metadata {
+            let (metadata_address, metadata_bump) = find_metadata_address(&fundraiser);
+            accounts.push(AccountMeta::new(metadata_address, false));
+            data.extend(MetadataIxData { bump: metadata_bump, ..metadata.clone() }.pack());
+        }
+        build(ProgramInstruction::Initialize, accounts, &data)
+    }
+}
+
+/// Contributes `amount` to the fundraiser of `maker`, credited to the
+/// beneficiary (the contributor itself by default)
+#[derive(Clone, Debug)]
+pub struct Contribute {
+    pub contributor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount: u64,
+    // Tier to back, `NO_TIER` to keep the current selection
+    pub tier: u8,
+    // Wallet credited instead of the contributor, it co-signs the gift
+    pub beneficiary: Option<Pubkey>,
+    // Pays the contributor account rent instead of the contributor
+    pub fee_payer: Option<Pubkey>,
+    // Round the fundraiser joined, required while it is in a round
+    pub round: Option<Pubkey>,
+    pub allowlist: Option<AllowlistProof>,
+}
+
+impl Contribute {
+    pub fn new(contributor: Pubkey, maker: Pubkey, mint_to_raise: Pubkey, amount: u64) -> Self {
+        Self {
+            contributor,
+            maker,
+            mint_to_raise,
+            amount,
+            tier: NO_TIER,
+            beneficiary: None,
+            fee_payer: None,
+            round: None,
+            allowlist: None,
+        }
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let beneficiary = self.beneficiary.unwrap_or(self.contributor);
+        let (fundraiser, fundraiser_bump) = find_fundraiser_address(&self.maker);
+        let (contributor_acc, contributor_bump) = find_contributor_address(
+            &fundraiser,
+            &beneficiary
+        );
+        let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+        let mut accounts = vec![
+            AccountMeta::new(self.contributor, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contributor_acc, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.contributor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(get_associated_token_address(&beneficiary, &receipt_mint), false)
+        ];
+        // The round comes first, then a gift beneficiary co-signing; the fee
+        // payer is found as the first extra signer other than the beneficiary
+        if let Some(round) = self.round {
+            accounts.push(AccountMeta::new(round, false));
+        }
+        if beneficiary != self.contributor {
+            accounts.push(AccountMeta::new_readonly(beneficiary, true));
+        }
+        if let Some(fee_payer) = self.fee_payer {
+            accounts.push(AccountMeta::new(fee_payer, true));
+        }
+
+        let mut data = (ContributeIxData {
+            amount: self.amount,
+            fundraiser_bump,
+            contributor_bump,
+            tier: self.tier,
+            beneficiary: beneficiary.to_bytes(),
+        }).pack();
+        if let Some(allowlist) = &self.allowlist {
+            data.extend(allowlist.pack());
+        }
+        build(ProgramInstruction::Contribute, accounts, &data)
+    }
+}
+
+/// Claims the raised funds of a successful fundraiser into the maker
+/// associated token account, which must exist beforehand
+#[derive(Clone, Debug)]
+pub struct CheckContribution {
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    // Set for registry fundraisers, whose registry is closed with the claim
+    pub uses_registry: bool,
+}
+
+impl CheckContribution {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let mut accounts = vec![
+            AccountMeta::new(self.maker, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new(get_associated_token_address(&self.maker, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        if self.uses_registry {
+            accounts.push(AccountMeta::new(find_registry_address(&fundraiser).0, false));
+        }
+        build(ProgramInstruction::CheckContribution, accounts, &[])
+    }
+}
+
+/// Refunds the contribution of `contributor` to a failed fundraiser
+#[derive(Clone, Debug)]
+pub struct Refund {
+    pub contributor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    // Fee payer that funded the contributor account, when not the contributor
+    pub rent_payer: Option<Pubkey>,
+}
+
+impl Refund {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+        let (contributor_acc, contributor_bump) = find_contributor_address(
+            &fundraiser,
+            &self.contributor
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.contributor, true),
+            AccountMeta::new_readonly(self.maker, false),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contributor_acc, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.contributor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(get_associated_token_address(&self.contributor, &receipt_mint), false)
+        ];
+        if let Some(rent_payer) = self.rent_payer {
+            accounts.push(AccountMeta::new(rent_payer, false));
+        }
+        let data = (RefundIxData { contributor_bump }).pack();
+        build(ProgramInstruction::Refund, accounts, &data)
+    }
+}
+
+/// Replaces the fundraiser metadata before the first contribution
+#[derive(Clone, Debug)]
+pub struct UpdateMetadata {
+    pub maker: Pubkey,
+    pub metadata: MetadataIxData,
+}
+
+impl UpdateMetadata {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (metadata, bump) = find_metadata_address(&fundraiser);
+        let accounts = vec![
+            AccountMeta::new_readonly(self.maker, true),
+            AccountMeta::new_readonly(fundraiser, false),
+            AccountMeta::new(metadata, false)
+        ];
+        let data = (MetadataIxData { bump, ..self.metadata.clone() }).pack();
+        build(ProgramInstruction::UpdateMetadata, accounts, &data)
+    }
+}
+
+/// Creates round `id` of `authority`. The matching pool vault, the round
+/// associated token account, must exist beforehand.
+#[derive(Clone, Debug)]
+pub struct InitializeRound {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub id: u64,
+    pub end_time: i64,
+}
+
+impl InitializeRound {
+    pub fn instruction(&self) -> Instruction {
+        let (round, bump) = find_round_address(&self.authority, self.id);
+        let accounts = vec![
+            AccountMeta::new(self.authority, true),
+            AccountMeta::new(round, false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new_readonly(get_associated_token_address(&round, &self.mint), false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        let data = (InitializeRoundIxData { id: self.id, end_time: self.end_time, bump }).pack();
+        build(ProgramInstruction::InitializeRound, accounts, &data)
+    }
+}
+
+/// Adds the fundraiser of `maker` to round `round_id` of `authority`
+#[derive(Clone, Debug)]
+pub struct JoinRound {
+    pub maker: Pubkey,
+    pub authority: Pubkey,
+    pub round_id: u64,
+}
+
+impl JoinRound {
+    pub fn instruction(&self) -> Instruction {
+        let accounts = vec![
+            AccountMeta::new_readonly(self.maker, true),
+            AccountMeta::new(find_fundraiser_address(&self.maker).0, false),
+            AccountMeta::new(find_round_address(&self.authority, self.round_id).0, false),
+            AccountMeta::new_readonly(self.authority, true)
+        ];
+        build(ProgramInstruction::JoinRound, accounts, &[])
+    }
+}
+
+/// Pays the match of the campaign at `index` in the round to its maker
+#[derive(Clone, Debug)]
+pub struct DistributeRound {
+    pub authority: Pubkey,
+    pub round_id: u64,
+    pub mint: Pubkey,
+    pub maker: Pubkey,
+    pub index: u8,
+}
+
+impl DistributeRound {
+    pub fn instruction(&self) -> Instruction {
+        let (round, _) = find_round_address(&self.authority, self.round_id);
+        let accounts = vec![
+            AccountMeta::new(round, false),
+            AccountMeta::new(get_associated_token_address(&round, &self.mint), false),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(get_associated_token_address(&self.maker, &self.mint), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+        let data = (DistributeRoundIxData { index: self.index }).pack();
+        build(ProgramInstruction::DistributeRound, accounts, &data)
+    }
+}
+
+/// Escrows up to `cap` tokens of `sponsor` to match contributions to the
+/// fundraiser of `maker`. The escrow, the pledge associated token account,
+/// must exist beforehand.
+#[derive(Clone, Debug)]
+pub struct CreateMatchPledge {
+    pub sponsor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub cap: u64,
+}
+
+impl CreateMatchPledge {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (pledge, bump) = find_match_pledge_address(&fundraiser, &self.sponsor);
+        let accounts = vec![
+            AccountMeta::new(self.sponsor, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new_readonly(fundraiser, false),
+            AccountMeta::new(pledge, false),
+            AccountMeta::new(get_associated_token_address(&pledge, &self.mint_to_raise), false),
+            AccountMeta::new(
+                get_associated_token_address(&self.sponsor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+        let data = (CreateMatchPledgeIxData { cap: self.cap, bump }).pack();
+        build(ProgramInstruction::CreateMatchPledge, accounts, &data)
+    }
+}
+
+/// Moves the matched share of new contributions into the fundraiser vault
+#[derive(Clone, Debug)]
+pub struct SettleMatch {
+    pub sponsor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+}
+
+impl SettleMatch {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (pledge, _) = find_match_pledge_address(&fundraiser, &self.sponsor);
+        let accounts = vec![
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(pledge, false),
+            AccountMeta::new(get_associated_token_address(&pledge, &self.mint_to_raise), false),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+        build(ProgramInstruction::SettleMatch, accounts, &[])
+    }
+}
+
+/// Returns the unused escrow to the sponsor and closes the pledge
+#[derive(Clone, Debug)]
+pub struct ReleaseMatch {
+    pub sponsor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+}
+
+impl ReleaseMatch {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (pledge, _) = find_match_pledge_address(&fundraiser, &self.sponsor);
+        let accounts = vec![
+            AccountMeta::new(self.sponsor, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(pledge, false),
+            AccountMeta::new(get_associated_token_address(&pledge, &self.mint_to_raise), false),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new(
+                get_associated_token_address(&self.sponsor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+        build(ProgramInstruction::ReleaseMatch, accounts, &[])
+    }
+}
+
+/// Moves `amount` of the position of `owner` to `recipient`
+#[derive(Clone, Debug)]
+pub struct TransferPosition {
+    pub owner: Pubkey,
+    pub maker: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    // Fee payer that funded the source account, needed when the whole
+    // position moves and it was not the owner
+    pub rent_payer: Option<Pubkey>,
+    // Allowlist entry of the recipient
+    pub allowlist: Option<AllowlistProof>,
+}
+
+impl TransferPosition {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (to_contributor, recipient_bump) = find_contributor_address(
+            &fundraiser,
+            &self.recipient
+        );
+        let (from_contributor, owner_bump) = find_contributor_address(&fundraiser, &self.owner);
+        let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+        let mut accounts = vec![
+            AccountMeta::new(self.owner, true),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(from_contributor, false),
+            AccountMeta::new(to_contributor, false),
+            AccountMeta::new_readonly(receipt_mint, false),
+            AccountMeta::new(get_associated_token_address(&self.owner, &receipt_mint), false),
+            AccountMeta::new(get_associated_token_address(&self.recipient, &receipt_mint), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false)
+        ];
+        if let Some(rent_payer) = self.rent_payer {
+            accounts.push(AccountMeta::new(rent_payer, false));
+        }
+
+        let mut data = (TransferPositionIxData {
+            amount: self.amount,
+            recipient: self.recipient.to_bytes(),
+            recipient_bump,
+            owner_bump,
+        }).pack();
+        if let Some(allowlist) = &self.allowlist {
+            data.extend(allowlist.pack());
+        }
+        build(ProgramInstruction::TransferPosition, accounts, &data)
+    }
+}
+
+/// Closes the contributor account of `contributor` once the funds are claimed
+#[derive(Clone, Debug)]
+pub struct CloseContributor {
+    pub contributor: Pubkey,
+    pub maker: Pubkey,
+    // Fee payer that funded the contributor account, when not the contributor
+    pub rent_payer: Option<Pubkey>,
+}
+
+impl CloseContributor {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (contributor_acc, contributor_bump) = find_contributor_address(
+            &fundraiser,
+            &self.contributor
+        );
+        let mut accounts = vec![
+            AccountMeta::new(self.contributor, true),
+            AccountMeta::new(self.maker, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(contributor_acc, false)
+        ];
+        if let Some(rent_payer) = self.rent_payer {
+            accounts.push(AccountMeta::new(rent_payer, false));
+        }
+        let data = (CloseContributorIxData { contributor_bump }).pack();
+        build(ProgramInstruction::CloseContributor, accounts, &data)
+    }
+}
+
+/// Switches the fundraiser of `maker` to the compact contributor registry,
+/// prepaying the rent of `capacity` entries
+#[derive(Clone, Debug)]
+pub struct InitializeRegistry {
+    pub maker: Pubkey,
+    pub capacity: u32,
+}
+
+impl InitializeRegistry {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (registry, bump) = find_registry_address(&fundraiser);
+        let accounts = vec![
+            AccountMeta::new(self.maker, true),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(registry, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        let data = (InitializeRegistryIxData { bump, capacity: self.capacity }).pack();
+        build(ProgramInstruction::InitializeRegistry, accounts, &data)
+    }
+}
+
+/// Contributes `amount` to a registry fundraiser
+#[derive(Clone, Debug)]
+pub struct ContributeRegistry {
+    pub contributor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount: u64,
+    pub allowlist: Option<AllowlistProof>,
+}
+
+impl ContributeRegistry {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+        let accounts = vec![
+            AccountMeta::new(self.contributor, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(find_registry_address(&fundraiser).0, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.contributor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(get_associated_token_address(&self.contributor, &receipt_mint), false)
+        ];
+
+        let mut data = (ContributeRegistryIxData { amount: self.amount }).pack();
+        if let Some(allowlist) = &self.allowlist {
+            data.extend(allowlist.pack());
+        }
+        build(ProgramInstruction::ContributeRegistry, accounts, &data)
+    }
+}
+
+/// Refunds a registry contributor of a failed fundraiser
+#[derive(Clone, Debug)]
+pub struct RefundRegistry {
+    pub contributor: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+}
+
+impl RefundRegistry {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+        let accounts = vec![
+            AccountMeta::new(self.contributor, true),
+            AccountMeta::new_readonly(self.mint_to_raise, false),
+            AccountMeta::new(fundraiser, false),
+            AccountMeta::new(find_registry_address(&fundraiser).0, false),
+            AccountMeta::new(
+                get_associated_token_address(&self.contributor, &self.mint_to_raise),
+                false
+            ),
+            AccountMeta::new(get_associated_token_address(&fundraiser, &self.mint_to_raise), false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(receipt_mint, false),
+            AccountMeta::new(get_associated_token_address(&self.contributor, &receipt_mint), false)
+        ];
+        build(ProgramInstruction::RefundRegistry, accounts, &[])
+    }
+}
+
+/// Rewrites `account` from an earlier layout into the current one
+#[derive(Clone, Debug)]
+pub struct Migrate {
+    pub payer: Pubkey,
+    pub account: Pubkey,
+    // Fundraiser and owner wallet of a first layout contributor account,
+    // the only layout that does not record its owner
+    pub contributor_seeds: Option<(Pubkey, Pubkey)>,
+}
+
+impl Migrate {
+    pub fn instruction(&self) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(self.payer, true),
+            AccountMeta::new(self.account, false),
+            AccountMeta::new_readonly(system_program::ID, false)
+        ];
+        let mut bump = 0;
+        if let Some((fundraiser, owner)) = self.contributor_seeds {
+            bump = find_contributor_address(&fundraiser, &owner).1;
+            accounts.push(AccountMeta::new(fundraiser, false));
+            accounts.push(AccountMeta::new_readonly(owner, false));
+        }
+        let data = (MigrateIxData { bump }).pack();
+        build(ProgramInstruction::Migrate, accounts, &data)
+    }
+}
+
+/// Reads the status of the fundraiser of `maker` as `FundraiserStatus`
+/// return data, with the contribution of `contributor` when set
+#[derive(Clone, Debug)]
+pub struct GetStatus {
+    pub maker: Pubkey,
+    pub contributor: Option<Pubkey>,
+}
+
+impl GetStatus {
+    pub fn instruction(&self) -> Instruction {
+        let (fundraiser, _) = find_fundraiser_address(&self.maker);
+        let mut accounts = vec![AccountMeta::new_readonly(fundraiser, false)];
+        let mut contributor_bump = 0;
+        if let Some(contributor) = self.contributor {
+            let (contributor_acc, bump) = find_contributor_address(&fundraiser, &contributor);
+            accounts.push(AccountMeta::new_readonly(contributor_acc, false));
+            contributor_bump = bump;
+        }
+        let data = (GetStatusIxData { contributor_bump }).pack();
+        build(ProgramInstruction::GetStatus, accounts, &data)
+    }
+}
@@ -0,0 +1,85 @@
+//! Host-side helpers for building program transactions, enabled with the
+//! `client` feature. Builders derive every PDA and associated token account
+//! from the wallets involved and return ready to sign instructions. Account
+//! decoders copy program accounts into owned structs and report the campaign
+//! status with the same rules the program applies. Event parsers pick the
+//! program events out of transaction logs.
+
+pub mod accounts;
+pub mod events;
+pub mod idl;
+pub mod instructions;
+pub use accounts::*;
+pub use events::*;
+pub use instructions::*;
+
+use solana_program::{ pubkey, pubkey::Pubkey };
+
+use crate::state::{
+    Contributor,
+    ContributorRegistry,
+    Fundraiser,
+    FundraiserMetadata,
+    MatchPledge,
+    Round,
+};
+
+pub const PROGRAM_ID: Pubkey = Pubkey::new_from_array(crate::ID);
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array(pinocchio_token::ID);
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+pub fn find_fundraiser_address(maker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Fundraiser::SEED.as_bytes(), maker.as_ref()], &PROGRAM_ID)
+}
+
+pub fn find_receipt_mint_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Fundraiser::RECEIPT_SEED.as_bytes(), fundraiser.as_ref()],
+        &PROGRAM_ID
+    )
+}
+
+pub fn find_contributor_address(fundraiser: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Contributor::SEED.as_bytes(), fundraiser.as_ref(), owner.as_ref()],
+        &PROGRAM_ID
+    )
+}
+
+pub fn find_metadata_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[FundraiserMetadata::SEED.as_bytes(), fundraiser.as_ref()],
+        &PROGRAM_ID
+    )
+}
+
+pub fn find_round_address(authority: &Pubkey, id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Round::SEED.as_bytes(), authority.as_ref(), &id.to_le_bytes()],
+        &PROGRAM_ID
+    )
+}
+
+pub fn find_match_pledge_address(fundraiser: &Pubkey, sponsor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MatchPledge::SEED.as_bytes(), fundraiser.as_ref(), sponsor.as_ref()],
+        &PROGRAM_ID
+    )
+}
+
+pub fn find_registry_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[ContributorRegistry::SEED.as_bytes(), fundraiser.as_ref()],
+        &PROGRAM_ID
+    )
+}
+
+/// Associated token account of `owner` for `mint` under the token program.
+/// Vaults and escrows are the associated accounts of the PDA owning them.
+pub fn get_associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID
+    ).0
+}
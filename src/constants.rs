@@ -1,4 +1,15 @@
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
+pub const MAX_DURATION_DAYS: u8 = 180;
 pub const SECONDS_TO_DAYS: i64 = 86400;
 pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 10;
-pub const PERCENTAGE_SCALER: u64 = 100;
\ No newline at end of file
+pub const PERCENTAGE_SCALER: u64 = 100;
+pub const MAX_NAME_LEN: usize = 32;
+pub const MAX_SYMBOL_LEN: usize = 10;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_TIERS: usize = 4;
+pub const NO_TIER: u8 = u8::MAX;
+pub const RECEIPT_RATE_SCALER: u64 = 10_000;
+pub const MAX_MERKLE_PROOF_LEN: usize = 24;
+pub const MAX_BONUS_PERIODS: usize = 3;
+pub const BONUS_BPS_SCALER: u64 = 10_000;
+pub const MAX_ROUND_CAMPAIGNS: usize = 16;
\ No newline at end of file
@@ -0,0 +1,389 @@
+//! Cross-program invocation helpers for on-chain callers, in the style of
+//! `pinocchio_token::instructions`. Each struct holds the accounts of one
+//! instruction and encodes its data, so callers never hand-encode the
+//! `ProgramInstruction` discriminators.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::{ get_return_data, slice_invoke_signed },
+    instruction::{ AccountMeta, Instruction, Signer },
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::{
+    constants::MAX_MERKLE_PROOF_LEN,
+    instruction::{
+        ContributeIxData,
+        FundraiserStatus,
+        GetStatusIxData,
+        ProgramInstruction,
+        RefundIxData,
+    },
+    utils::DataLen,
+    wire::IxData,
+};
+
+/// Contributes `amount` from the contributor token account to the vault,
+/// credited to `beneficiary`, or to the contributor itself when it is `None`.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` The contributor paying the tokens.
+///   1. `[]` The mint to raise.
+///   2. `[WRITE]` The fundraiser account.
+///   3. `[WRITE]` The contributor account of the beneficiary, created if empty.
+///   4. `[WRITE]` The contributor token account.
+///   5. `[WRITE]` The fundraiser vault.
+///   6. `[]` The token program.
+///   7. `[]` The system program.
+///   8. `[WRITE]` The receipt mint.
+///   9. `[WRITE]` The beneficiary receipt token account.
+///   10. `[WRITE]` (optional) The round, while the fundraiser is in one.
+///   11. `[SIGNER]` (optional) The beneficiary, when it is not the contributor.
+///   12. `[WRITE, SIGNER]` (optional) The payer of the contributor account rent.
+pub struct Contribute<'a> {
+    pub contributor: &'a AccountInfo,
+    pub mint_to_raise: &'a AccountInfo,
+    pub fundraiser: &'a AccountInfo,
+    pub contributor_acc: &'a AccountInfo,
+    pub contributor_ata: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub receipt_mint: &'a AccountInfo,
+    pub contributor_receipt_ata: &'a AccountInfo,
+    pub round: Option<&'a AccountInfo>,
+    pub fee_payer: Option<&'a AccountInfo>,
+    pub amount: u64,
+    pub fundraiser_bump: u8,
+    pub contributor_bump: u8,
+    // Tier to back, `NO_TIER` to keep the current selection
+    pub tier: u8,
+    // Wallet credited with the contribution instead of the contributor, it
+    // co-signs the instruction
+    pub beneficiary: Option<&'a AccountInfo>,
+    // Cap and merkle proof of the beneficiary on allowlisted campaigns
+    pub allowlist: Option<(u64, &'a [[u8; 32]])>,
+}
+
+impl Contribute<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, account_infos| {
+            slice_invoke_signed(instruction, account_infos, signers)
+        })?
+    }
+
+    /// Hands the encoded instruction and its account infos to `f` instead
+    /// of invoking it
+    pub fn with_instruction<R>(
+        &self,
+        f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R
+    ) -> Result<R, ProgramError> {
+        // Unused trailing slots are cut off below
+        let mut account_metas = [
+            AccountMeta::writable_signer(self.contributor.key()),
+            AccountMeta::readonly(self.mint_to_raise.key()),
+            AccountMeta::writable(self.fundraiser.key()),
+            AccountMeta::writable(self.contributor_acc.key()),
+            AccountMeta::writable(self.contributor_ata.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::writable(self.receipt_mint.key()),
+            AccountMeta::writable(self.contributor_receipt_ata.key()),
+            AccountMeta::readonly(self.fundraiser.key()),
+            AccountMeta::readonly(self.fundraiser.key()),
+            AccountMeta::readonly(self.fundraiser.key()),
+        ];
+        let mut account_infos = [
+            self.contributor,
+            self.mint_to_raise,
+            self.fundraiser,
+            self.contributor_acc,
+            self.contributor_ata,
+            self.vault,
+            self.token_program,
+            self.system_program,
+            self.receipt_mint,
+            self.contributor_receipt_ata,
+            self.fundraiser,
+            self.fundraiser,
+            self.fundraiser,
+        ];
+        let mut accounts_len = 10;
+        // The round comes first, then a beneficiary co-signing; the fee payer
+        // is found as the first extra signer other than the beneficiary
+        if let Some(round) = self.round {
+            account_metas[accounts_len] = AccountMeta::writable(round.key());
+            account_infos[accounts_len] = round;
+            accounts_len += 1;
+        }
+        if let Some(beneficiary) = self.beneficiary {
+            account_metas[accounts_len] = AccountMeta::readonly_signer(beneficiary.key());
+            account_infos[accounts_len] = beneficiary;
+            accounts_len += 1;
+        }
+        if let Some(fee_payer) = self.fee_payer {
+            account_metas[accounts_len] = AccountMeta::writable_signer(fee_payer.key());
+            account_infos[accounts_len] = fee_payer;
+            accounts_len += 1;
+        }
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..44]: `ContributeIxData` (43 bytes)
+        // -  [44..]: (optional) allowlist cap (8 bytes, u64) and proof nodes (32 bytes each)
+        let mut data = [0; 1 + ContributeIxData::LEN + 8 + 32 * MAX_MERKLE_PROOF_LEN];
+        data[0] = ProgramInstruction::Contribute as u8;
+        (ContributeIxData {
+            amount: self.amount,
+            fundraiser_bump: self.fundraiser_bump,
+            contributor_bump: self.contributor_bump,
+            tier: self.tier,
+            beneficiary: *self.beneficiary.unwrap_or(self.contributor).key(),
+        }).pack_into(&mut data[1..]);
+        let mut data_len = 1 + ContributeIxData::LEN;
+        if let Some((cap, proof)) = self.allowlist {
+            if proof.len() > MAX_MERKLE_PROOF_LEN {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            data[data_len..data_len + 8].copy_from_slice(&cap.to_le_bytes());
+            data_len += 8;
+            for node in proof {
+                data[data_len..data_len + 32].copy_from_slice(node);
+                data_len += 32;
+            }
+        }
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &data[..data_len],
+        };
+        Ok(f(&instruction, &account_infos[..accounts_len]))
+    }
+}
+
+/// Returns the contribution of a failed fundraiser to the contributor and
+/// closes the contributor account.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` The contributor, owner of the contributor account.
+///   1. `[]` The fundraiser maker.
+///   2. `[]` The mint to raise.
+///   3. `[WRITE]` The fundraiser account.
+///   4. `[WRITE]` The contributor account.
+///   5. `[WRITE]` The contributor token account.
+///   6. `[WRITE]` The fundraiser vault.
+///   7. `[]` The system program.
+///   8. `[]` The token program.
+///   9. `[WRITE]` The receipt mint.
+///   10. `[WRITE]` The contributor receipt token account.
+///   11. `[WRITE]` (optional) The payer of the contributor account rent, when
+///       not the contributor.
+pub struct Refund<'a> {
+    pub contributor: &'a AccountInfo,
+    pub maker: &'a AccountInfo,
+    pub mint_to_raise: &'a AccountInfo,
+    pub fundraiser: &'a AccountInfo,
+    pub contributor_acc: &'a AccountInfo,
+    pub contributor_ata: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub receipt_mint: &'a AccountInfo,
+    pub contributor_receipt_ata: &'a AccountInfo,
+    pub rent_payer: Option<&'a AccountInfo>,
+    pub contributor_bump: u8,
+}
+
+impl Refund<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, account_infos| {
+            slice_invoke_signed(instruction, account_infos, signers)
+        })
+    }
+
+    /// Hands the encoded instruction and its account infos to `f` instead
+    /// of invoking it
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let rent_payer = self.rent_payer.unwrap_or(self.contributor);
+        let account_metas = [
+            AccountMeta::writable_signer(self.contributor.key()),
+            AccountMeta::readonly(self.maker.key()),
+            AccountMeta::readonly(self.mint_to_raise.key()),
+            AccountMeta::writable(self.fundraiser.key()),
+            AccountMeta::writable(self.contributor_acc.key()),
+            AccountMeta::writable(self.contributor_ata.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::writable(self.receipt_mint.key()),
+            AccountMeta::writable(self.contributor_receipt_ata.key()),
+            AccountMeta::writable(rent_payer.key()),
+        ];
+        let account_infos = [
+            self.contributor,
+            self.maker,
+            self.mint_to_raise,
+            self.fundraiser,
+            self.contributor_acc,
+            self.contributor_ata,
+            self.vault,
+            self.system_program,
+            self.token_program,
+            self.receipt_mint,
+            self.contributor_receipt_ata,
+            rent_payer,
+        ];
+        let accounts_len = if self.rent_payer.is_some() { 12 } else { 11 };
+
+        let mut data = [0; 1 + RefundIxData::LEN];
+        data[0] = ProgramInstruction::Refund as u8;
+        (RefundIxData { contributor_bump: self.contributor_bump }).pack_into(&mut data[1..]);
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &data,
+        };
+        f(&instruction, &account_infos[..accounts_len])
+    }
+}
+
+/// Claims the funds of a fundraiser that met its target into the maker
+/// token account.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` The fundraiser maker.
+///   1. `[]` The mint to raise.
+///   2. `[WRITE]` The fundraiser account.
+///   3. `[WRITE]` The fundraiser vault, closed by the claim.
+///   4. `[WRITE]` The maker token account.
+///   5. `[]` The token program.
+///   6. `[]` The system program.
+///   7. `[WRITE]` (optional) The contributor registry, for registry fundraisers.
+pub struct CheckContribution<'a> {
+    pub maker: &'a AccountInfo,
+    pub mint_to_raise: &'a AccountInfo,
+    pub fundraiser: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub maker_ata: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub registry: Option<&'a AccountInfo>,
+}
+
+impl CheckContribution<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, account_infos| {
+            slice_invoke_signed(instruction, account_infos, signers)
+        })
+    }
+
+    /// Hands the encoded instruction and its account infos to `f` instead
+    /// of invoking it
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let registry = self.registry.unwrap_or(self.fundraiser);
+        let account_metas = [
+            AccountMeta::writable_signer(self.maker.key()),
+            AccountMeta::readonly(self.mint_to_raise.key()),
+            AccountMeta::writable(self.fundraiser.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::writable(self.maker_ata.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::writable(registry.key()),
+        ];
+        let account_infos = [
+            self.maker,
+            self.mint_to_raise,
+            self.fundraiser,
+            self.vault,
+            self.maker_ata,
+            self.token_program,
+            self.system_program,
+            registry,
+        ];
+        let accounts_len = if self.registry.is_some() { 8 } else { 7 };
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &[ProgramInstruction::CheckContribution as u8],
+        };
+        f(&instruction, &account_infos[..accounts_len])
+    }
+}
+
+/// Reads the status of a fundraiser and, optionally, how much one of its
+/// contributors put in.
+///
+/// ### Accounts:
+///   0. `[]` The fundraiser account.
+///   1. `[]` (optional) A contributor account of the fundraiser.
+pub struct GetStatus<'a> {
+    pub fundraiser: &'a AccountInfo,
+    pub contributor: Option<&'a AccountInfo>,
+    // Bump of the contributor account, ignored without one
+    pub contributor_bump: u8,
+}
+
+impl GetStatus<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> Result<FundraiserStatus, ProgramError> {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> Result<FundraiserStatus, ProgramError> {
+        self.with_instruction(|instruction, account_infos| {
+            slice_invoke_signed(instruction, account_infos, signers)
+        })?;
+
+        // Return data is cleared before every invocation and the program
+        // invokes nobody here, so it can only come from us
+        let return_data = get_return_data().ok_or(ProgramError::InvalidAccountData)?;
+        if return_data.program_id() != &crate::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        FundraiserStatus::unpack(&return_data)
+    }
+
+    /// Hands the encoded instruction and its account infos to `f` instead
+    /// of invoking it
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let mut data = [0; 1 + GetStatusIxData::LEN];
+        data[0] = ProgramInstruction::GetStatus as u8;
+        (GetStatusIxData { contributor_bump: self.contributor_bump }).pack_into(&mut data[1..]);
+
+        let contributor = self.contributor.unwrap_or(self.fundraiser);
+        let account_metas = [
+            AccountMeta::readonly(self.fundraiser.key()),
+            AccountMeta::readonly(contributor.key()),
+        ];
+        let account_infos = [self.fundraiser, contributor];
+        let accounts_len = if self.contributor.is_some() { 2 } else { 1 };
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &data,
+        };
+        f(&instruction, &account_infos[..accounts_len])
+    }
+}
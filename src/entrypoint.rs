@@ -1,4 +1,4 @@
-use crate::instruction::{ self, ProgramInstruction };
+use crate::{ error::FundraiserError, instruction::{ self, ProgramInstruction } };
 use pinocchio::{
     account_info::AccountInfo,
     no_allocator,
@@ -27,7 +27,7 @@ fn process_instruction(
         .split_first()
         .ok_or(ProgramError::InvalidInstructionData)?;
 
-    match ProgramInstruction::try_from(ix_disc)? {
+    let result = match ProgramInstruction::try_from(ix_disc)? {
         ProgramInstruction::Initialize => {
             log!("Initialize instruction");
             instruction::initialize::process_initialize(accounts, instruction_data)
@@ -44,5 +44,69 @@ fn process_instruction(
             log!("Refund instruction");
             instruction::refund::process_refund(accounts, instruction_data)
         }
+        ProgramInstruction::UpdateMetadata => {
+            log!("UpdateMetadata instruction");
+            instruction::metadata::process_update_metadata(accounts, instruction_data)
+        }
+        ProgramInstruction::InitializeRound => {
+            log!("InitializeRound instruction");
+            instruction::round::process_initialize_round(accounts, instruction_data)
+        }
+        ProgramInstruction::JoinRound => {
+            log!("JoinRound instruction");
+            instruction::round::process_join_round(accounts, instruction_data)
+        }
+        ProgramInstruction::DistributeRound => {
+            log!("DistributeRound instruction");
+            instruction::round::process_distribute_round(accounts, instruction_data)
+        }
+        ProgramInstruction::CreateMatchPledge => {
+            log!("CreateMatchPledge instruction");
+            instruction::match_pledge::process_create_match_pledge(accounts, instruction_data)
+        }
+        ProgramInstruction::SettleMatch => {
+            log!("SettleMatch instruction");
+            instruction::match_pledge::process_settle_match(accounts, instruction_data)
+        }
+        ProgramInstruction::ReleaseMatch => {
+            log!("ReleaseMatch instruction");
+            instruction::match_pledge::process_release_match(accounts, instruction_data)
+        }
+        ProgramInstruction::TransferPosition => {
+            log!("TransferPosition instruction");
+            instruction::position::process_transfer_position(accounts, instruction_data)
+        }
+        ProgramInstruction::CloseContributor => {
+            log!("CloseContributor instruction");
+            instruction::close::process_close_contributor(accounts, instruction_data)
+        }
+        ProgramInstruction::InitializeRegistry => {
+            log!("InitializeRegistry instruction");
+            instruction::registry::process_initialize_registry(accounts, instruction_data)
+        }
+        ProgramInstruction::ContributeRegistry => {
+            log!("ContributeRegistry instruction");
+            instruction::registry::process_contribute_registry(accounts, instruction_data)
+        }
+        ProgramInstruction::RefundRegistry => {
+            log!("RefundRegistry instruction");
+            instruction::registry::process_refund_registry(accounts, instruction_data)
+        }
+        ProgramInstruction::Migrate => {
+            log!("Migrate instruction");
+            instruction::migrate::process_migrate(accounts, instruction_data)
+        }
+        ProgramInstruction::GetStatus => {
+            log!("GetStatus instruction");
+            instruction::status::process_get_status(accounts, instruction_data)
+        }
+    };
+
+    // Custom codes are opaque in the transaction logs, spell out our own
+    if let Err(ProgramError::Custom(code)) = result {
+        if let Ok(error) = FundraiserError::try_from(code) {
+            log!("Error: {}", error.message());
+        }
     }
+    result
 }
@@ -1,23 +1,98 @@
 use pinocchio::program_error::ProgramError;
 
-#[derive(Clone, PartialEq)]
+/// Custom program errors, described by [`FundraiserError::message`]. Codes are
+/// part of the program interface, new variants are appended with the next free
+/// code and existing codes are never reused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
 pub enum FundraiserError {
-    // The amount to raise has not been met
-    TargetNotMet,
-    // The amount to raise has been achieved
-    TargetMet,
-    // The contribution is too big
-    ContributionTooBig,
-    // The contribution is too small
-    ContributionTooSmall,
-    // The maximum amount to contribute has been reached
-    MaximumContributionsReached,
-    // The fundraiser has not ended yet
-    FundraiserNotEnded,
-    // The fundraiser has ended
-    FundraiserEnded,
-    // Invalid total amount. i should be bigger than 3
-    InvalidAmount,
+    TargetNotMet = 0,
+    TargetMet = 1,
+    ContributionTooBig = 2,
+    ContributionTooSmall = 3,
+    MaximumContributionsReached = 4,
+    FundraiserNotEnded = 5,
+    FundraiserEnded = 6,
+    InvalidAmount = 7,
+    InvalidMaker = 8,
+    MetadataLocked = 9,
+    InvalidTier = 10,
+    TierFull = 11,
+    TierMinimumNotMet = 12,
+    InvalidReceiptRate = 13,
+    NotAllowlisted = 14,
+    AllowlistCapExceeded = 15,
+    InvalidBonusSchedule = 16,
+    RoundFull = 17,
+    RoundEnded = 18,
+    RoundNotEnded = 19,
+    InvalidRound = 20,
+    NotEligibleForMatch = 21,
+    MatchAlreadyPaid = 22,
+    FundraiserSettled = 23,
+    FundraiserNotSettled = 24,
+    RegistryMode = 25,
+    RegistryNotSupported = 26,
+    NotInRegistry = 27,
+    InvalidAccountVersion = 28,
+    InvalidDuration = 29,
+    InvalidMintOwner = 30,
+    MintNotInitialized = 31,
+    InvalidVaultMint = 32,
+    RegistryFull = 33,
+    InvalidVaultOwner = 34,
+    InvalidMetadata = 35,
+    GiftInRound = 36,
+}
+
+impl FundraiserError {
+    /// Short description of the error, also logged by the program on failure
+    pub const fn message(&self) -> &'static str {
+        match self {
+            Self::TargetNotMet => "The amount to raise has not been met",
+            Self::TargetMet => "The amount to raise has been achieved",
+            Self::ContributionTooBig => "The contribution is too big",
+            Self::ContributionTooSmall => "The contribution is too small",
+            Self::MaximumContributionsReached =>
+                "The maximum amount to contribute has been reached",
+            Self::FundraiserNotEnded => "The fundraiser has not ended yet",
+            Self::FundraiserEnded => "The fundraiser has ended",
+            Self::InvalidAmount =>
+                "Invalid amount, the target must be at least `MIN_AMOUNT_TO_RAISE` whole tokens",
+            Self::InvalidMaker => "The signer is not the maker of the fundraiser",
+            Self::MetadataLocked =>
+                "Metadata can no longer be changed once contributions have been made",
+            Self::InvalidTier => "The tier does not exist or differs from the one already selected",
+            Self::TierFull => "The tier has reached its maximum number of backers",
+            Self::TierMinimumNotMet => "The contribution is below the tier minimum",
+            Self::InvalidReceiptRate => "The receipt rate must be greater than zero",
+            Self::NotAllowlisted => "The contributor is not part of the allowlist",
+            Self::AllowlistCapExceeded => "The contribution exceeds the contributor allowlist cap",
+            Self::InvalidBonusSchedule =>
+                "The bonus periods are not ordered by increasing duration",
+            Self::RoundFull => "The round has no room for more campaigns",
+            Self::RoundEnded => "The round has ended",
+            Self::RoundNotEnded => "The round has not ended yet",
+            Self::InvalidRound => "The fundraiser is not part of this round or already joined one",
+            Self::NotEligibleForMatch => "The campaign did not succeed or has no matching weight",
+            Self::MatchAlreadyPaid => "The match for this campaign has already been paid",
+            Self::FundraiserSettled => "The raised funds have already been claimed by the maker",
+            Self::FundraiserNotSettled => "The raised funds have not been claimed by the maker yet",
+            Self::RegistryMode => "The fundraiser tracks contributions in its registry",
+            Self::RegistryNotSupported => "The fundraiser cannot switch to a registry",
+            Self::NotInRegistry => "The contributor has no entry in the registry",
+            Self::InvalidAccountVersion => "The account was written with another layout version",
+            Self::InvalidDuration =>
+                "The fundraiser duration must be between one day and `MAX_DURATION_DAYS`",
+            Self::InvalidMintOwner => "The mint to raise is not owned by the token program",
+            Self::MintNotInitialized => "The mint to raise has not been initialized",
+            Self::InvalidVaultMint => "The vault does not hold the mint to raise",
+            Self::RegistryFull => "The registry has no prepaid rent left for another entry",
+            Self::InvalidVaultOwner => "The vault is not owned by the fundraiser",
+            Self::InvalidMetadata => "Metadata strings must be UTF-8 followed by zero padding",
+            Self::GiftInRound => "Contributions to a campaign in a round must credit the payer",
+        }
+    }
 }
 
 impl From<FundraiserError> for ProgramError {
@@ -25,3 +100,60 @@ impl From<FundraiserError> for ProgramError {
         Self::Custom(e as u32)
     }
 }
+
+impl TryFrom<u32> for FundraiserError {
+    type Error = ProgramError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundraiserError::TargetNotMet),
+            1 => Ok(FundraiserError::TargetMet),
+            2 => Ok(FundraiserError::ContributionTooBig),
+            3 => Ok(FundraiserError::ContributionTooSmall),
+            4 => Ok(FundraiserError::MaximumContributionsReached),
+            5 => Ok(FundraiserError::FundraiserNotEnded),
+            6 => Ok(FundraiserError::FundraiserEnded),
+            7 => Ok(FundraiserError::InvalidAmount),
+            8 => Ok(FundraiserError::InvalidMaker),
+            9 => Ok(FundraiserError::MetadataLocked),
+            10 => Ok(FundraiserError::InvalidTier),
+            11 => Ok(FundraiserError::TierFull),
+            12 => Ok(FundraiserError::TierMinimumNotMet),
+            13 => Ok(FundraiserError::InvalidReceiptRate),
+            14 => Ok(FundraiserError::NotAllowlisted),
+            15 => Ok(FundraiserError::AllowlistCapExceeded),
+            16 => Ok(FundraiserError::InvalidBonusSchedule),
+            17 => Ok(FundraiserError::RoundFull),
+            18 => Ok(FundraiserError::RoundEnded),
+            19 => Ok(FundraiserError::RoundNotEnded),
+            20 => Ok(FundraiserError::InvalidRound),
+            21 => Ok(FundraiserError::NotEligibleForMatch),
+            22 => Ok(FundraiserError::MatchAlreadyPaid),
+            23 => Ok(FundraiserError::FundraiserSettled),
+            24 => Ok(FundraiserError::FundraiserNotSettled),
+            25 => Ok(FundraiserError::RegistryMode),
+            26 => Ok(FundraiserError::RegistryNotSupported),
+            27 => Ok(FundraiserError::NotInRegistry),
+            28 => Ok(FundraiserError::InvalidAccountVersion),
+            29 => Ok(FundraiserError::InvalidDuration),
+            30 => Ok(FundraiserError::InvalidMintOwner),
+            31 => Ok(FundraiserError::MintNotInitialized),
+            32 => Ok(FundraiserError::InvalidVaultMint),
+            33 => Ok(FundraiserError::RegistryFull),
+            34 => Ok(FundraiserError::InvalidVaultOwner),
+            35 => Ok(FundraiserError::InvalidMetadata),
+            36 => Ok(FundraiserError::GiftInRound),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for FundraiserError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FundraiserError {}
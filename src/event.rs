@@ -0,0 +1,293 @@
+//! Structured events for indexers. Every event is logged with `sol_log_data`
+//! as a single field: the `EventDiscriminator` byte followed by the event
+//! fields in the instruction wire format. RPC nodes render it as a
+//! `Program data: <base64>` log line.
+
+use pinocchio::{ log::sol_log_data, program_error::ProgramError, pubkey::Pubkey };
+
+use crate::{ utils::DataLen, wire::{ WireReader, WireWriter } };
+
+/// Leading type tag of every event
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventDiscriminator {
+    FundraiserCreated = 0,
+    Contributed,
+    Refunded,
+    Finalized,
+    // Reserved, the program has no way to cancel a fundraiser yet
+    Cancelled,
+    Matched,
+}
+
+impl TryFrom<u8> for EventDiscriminator {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EventDiscriminator::FundraiserCreated),
+            1 => Ok(EventDiscriminator::Contributed),
+            2 => Ok(EventDiscriminator::Refunded),
+            3 => Ok(EventDiscriminator::Finalized),
+            4 => Ok(EventDiscriminator::Cancelled),
+            5 => Ok(EventDiscriminator::Matched),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+/// Largest encoded event, discriminator included
+pub const MAX_EVENT_LEN: usize = 1 + FundraiserCreated::LEN;
+
+/// Event with a fixed-size wire encoding of `LEN` bytes, tag excluded
+pub trait Event: DataLen + Sized {
+    const DISCRIMINATOR: EventDiscriminator;
+
+    /// Decodes exactly `Self::LEN` bytes following the tag
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError>;
+
+    /// Encodes into the first `Self::LEN` bytes of `dst`, which must be large enough
+    fn pack_into(&self, dst: &mut [u8]);
+
+    /// Logs the tagged event as a single `sol_log_data` field
+    fn emit(&self) {
+        let mut data = [0; MAX_EVENT_LEN];
+        data[0] = Self::DISCRIMINATOR as u8;
+        self.pack_into(&mut data[1..]);
+        sol_log_data(&[&data[..1 + Self::LEN]]);
+    }
+}
+
+/// Wire layout (113 bytes): `fundraiser` (32), `maker` (32),
+/// `mint_to_raise` (32), `amount_to_raise` (8), `time_started` (8), `duration` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FundraiserCreated {
+    pub fundraiser: Pubkey,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount_to_raise: u64,
+    pub time_started: i64,
+    pub duration: u8,
+}
+
+impl DataLen for FundraiserCreated {
+    const LEN: usize = 113;
+}
+
+impl Event for FundraiserCreated {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::FundraiserCreated;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            fundraiser: reader.read_bytes()?,
+            maker: reader.read_bytes()?,
+            mint_to_raise: reader.read_bytes()?,
+            amount_to_raise: reader.read_u64()?,
+            time_started: reader.read_i64()?,
+            duration: reader.read_u8()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.fundraiser);
+        writer.write_bytes(&self.maker);
+        writer.write_bytes(&self.mint_to_raise);
+        writer.write_u64(self.amount_to_raise);
+        writer.write_i64(self.time_started);
+        writer.write_u8(self.duration);
+    }
+}
+
+/// Wire layout (80 bytes): `fundraiser` (32), `contributor` (32), `amount` (8),
+/// `current_amount` (8)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Contributed {
+    pub fundraiser: Pubkey,
+    // Wallet credited with the contribution, not necessarily the signer
+    pub contributor: Pubkey,
+    pub amount: u64,
+    // Fundraiser total including this contribution
+    pub current_amount: u64,
+}
+
+impl DataLen for Contributed {
+    const LEN: usize = 80;
+}
+
+impl Event for Contributed {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::Contributed;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            fundraiser: reader.read_bytes()?,
+            contributor: reader.read_bytes()?,
+            amount: reader.read_u64()?,
+            current_amount: reader.read_u64()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.fundraiser);
+        writer.write_bytes(&self.contributor);
+        writer.write_u64(self.amount);
+        writer.write_u64(self.current_amount);
+    }
+}
+
+/// Wire layout (72 bytes): `fundraiser` (32), `contributor` (32), `amount` (8)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Refunded {
+    pub fundraiser: Pubkey,
+    pub contributor: Pubkey,
+    pub amount: u64,
+}
+
+impl DataLen for Refunded {
+    const LEN: usize = 72;
+}
+
+impl Event for Refunded {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::Refunded;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            fundraiser: reader.read_bytes()?,
+            contributor: reader.read_bytes()?,
+            amount: reader.read_u64()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.fundraiser);
+        writer.write_bytes(&self.contributor);
+        writer.write_u64(self.amount);
+    }
+}
+
+/// Wire layout (72 bytes): `fundraiser` (32), `maker` (32), `amount` (8)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Finalized {
+    pub fundraiser: Pubkey,
+    pub maker: Pubkey,
+    // Amount claimed by the maker
+    pub amount: u64,
+}
+
+impl DataLen for Finalized {
+    const LEN: usize = 72;
+}
+
+impl Event for Finalized {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::Finalized;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            fundraiser: reader.read_bytes()?,
+            maker: reader.read_bytes()?,
+            amount: reader.read_u64()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.fundraiser);
+        writer.write_bytes(&self.maker);
+        writer.write_u64(self.amount);
+    }
+}
+
+/// Wire layout (32 bytes): `fundraiser` (32). Reserved, never emitted yet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Cancelled {
+    pub fundraiser: Pubkey,
+}
+
+impl DataLen for Cancelled {
+    const LEN: usize = 32;
+}
+
+impl Event for Cancelled {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::Cancelled;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self { fundraiser: reader.read_bytes()? })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_bytes(&self.fundraiser);
+    }
+}
+
+/// Wire layout (80 bytes): `fundraiser` (32), `sponsor` (32), `amount` (8),
+/// `current_amount` (8)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Matched {
+    pub fundraiser: Pubkey,
+    pub sponsor: Pubkey,
+    // Amount moved from the pledge escrow into the vault
+    pub amount: u64,
+    // Fundraiser total including this match
+    pub current_amount: u64,
+}
+
+impl DataLen for Matched {
+    const LEN: usize = 80;
+}
+
+impl Event for Matched {
+    const DISCRIMINATOR: EventDiscriminator = EventDiscriminator::Matched;
+
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            fundraiser: reader.read_bytes()?,
+            sponsor: reader.read_bytes()?,
+            amount: reader.read_u64()?,
+            current_amount: reader.read_u64()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.fundraiser);
+        writer.write_bytes(&self.sponsor);
+        writer.write_u64(self.amount);
+        writer.write_u64(self.current_amount);
+    }
+}
+
+/// Any event logged by the program
+#[derive(Clone, Debug, PartialEq)]
+pub enum FundraiserEvent {
+    FundraiserCreated(FundraiserCreated),
+    Contributed(Contributed),
+    Refunded(Refunded),
+    Finalized(Finalized),
+    Cancelled(Cancelled),
+    Matched(Matched),
+}
+
+impl FundraiserEvent {
+    /// Decodes a tagged event, the data of a single `sol_log_data` field
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let (tag, data) = data.split_first().ok_or(ProgramError::InvalidArgument)?;
+        Ok(match EventDiscriminator::try_from(*tag)? {
+            EventDiscriminator::FundraiserCreated => {
+                Self::FundraiserCreated(FundraiserCreated::unpack(data)?)
+            }
+            EventDiscriminator::Contributed => Self::Contributed(Contributed::unpack(data)?),
+            EventDiscriminator::Refunded => Self::Refunded(Refunded::unpack(data)?),
+            EventDiscriminator::Finalized => Self::Finalized(Finalized::unpack(data)?),
+            EventDiscriminator::Cancelled => Self::Cancelled(Cancelled::unpack(data)?),
+            EventDiscriminator::Matched => Self::Matched(Matched::unpack(data)?),
+        })
+    }
+}
@@ -9,7 +9,12 @@ use pinocchio_token::{
     state::Mint,
     state::TokenAccount,
 };
-use crate::{ error::FundraiserError, state::Fundraiser, utils::{ load_acc, DataLen } };
+use crate::{
+    error::FundraiserError,
+    event::{ Event, Finalized },
+    state::Fundraiser,
+    utils::{ load_acc_mut, DataLen },
+};
 
 impl DataLen for Mint {
     const LEN: usize = core::mem::size_of::<Mint>();
@@ -28,7 +33,7 @@ pub fn process_check_contribution(accounts: &[AccountInfo], _data: &[u8]) -> Pro
         maker_ata,
         _token_program,
         _system_program,
-        _rest @ ..,
+        rest @ ..,
     ] = accounts else {
         return Err(ProgramError::InvalidAccountData);
     };
@@ -37,12 +42,18 @@ pub fn process_check_contribution(accounts: &[AccountInfo], _data: &[u8]) -> Pro
         return Err(ProgramError::MissingRequiredSignature);
     }
 
-    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser.borrow_data_unchecked())? };
-    if fundraiser_state.current_amount < fundraiser_state.amount_to_raise {
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    if fundraiser_state.settled.get() {
+        return Err(FundraiserError::FundraiserSettled.into());
+    }
+    if fundraiser_state.current_amount.get() < fundraiser_state.amount_to_raise.get() {
         return Err(FundraiserError::TargetNotMet.into());
     }
 
     // Transfer the funds to the maker
+    let amount = fundraiser_state.current_amount.get();
     let mint_state = Mint::from_account_info(mint_to_raise)?;
 
     let bump_seed = [fundraiser_state.bump];
@@ -54,25 +65,53 @@ pub fn process_check_contribution(accounts: &[AccountInfo], _data: &[u8]) -> Pro
 
     let fundraiser_signer = Signer::from(&fundraiser_seeds[..]);
     (TransferChecked {
-        amount: fundraiser_state.current_amount,
+        amount,
         from: vault,
         to: maker_ata,
         authority: fundraiser,
         mint: mint_to_raise,
         decimals: mint_state.decimals(),
-    }).invoke_signed(&[fundraiser_signer.clone()])?;
+    }).invoke_signed(core::slice::from_ref(&fundraiser_signer))?;
 
     // Close the vault account
     (CloseAccount {
         account: vault,
         destination: maker,
         authority: fundraiser,
-    }).invoke_signed(&[fundraiser_signer.clone()])?;
+    }).invoke_signed(core::slice::from_ref(&fundraiser_signer))?;
+
+    // Contributor accounts still need the fundraiser to be closed, the last
+    // one to close also closes the fundraiser
+    fundraiser_state.settled.set(true);
+    // Registry entries have nothing left to refund once the funds are claimed
+    if fundraiser_state.uses_registry() {
+        let [registry, ..] = rest else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if *registry.key() != fundraiser_state.registry {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        unsafe {
+            *maker.borrow_mut_lamports_unchecked() += *registry.borrow_lamports_unchecked();
+        }
+        registry.close()?;
+    }
 
-    // Close the fundraiser account
+    (Finalized {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        amount,
+    }).emit();
+    if fundraiser_state.contributor_count.get() == 0 {
+        close_fundraiser(maker, fundraiser)?;
+    }
+    Ok(())
+}
+
+/// Closes the fundraiser account, returning its rent to the maker
+pub(crate) fn close_fundraiser(maker: &AccountInfo, fundraiser: &AccountInfo) -> ProgramResult {
     unsafe {
         *maker.borrow_mut_lamports_unchecked() += *fundraiser.borrow_lamports_unchecked();
     }
-    fundraiser.close()?;
-    Ok(())
+    fundraiser.close()
 }
@@ -0,0 +1,82 @@
+use pinocchio::{ account_info::AccountInfo, program_error::ProgramError, ProgramResult };
+
+use crate::{
+    error::FundraiserError,
+    instruction::{ checker::close_fundraiser, refund::find_rent_payer },
+    state::{ Contributor, Fundraiser },
+    utils::{ load_acc_mut, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (1 byte): `contributor_bump` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CloseContributorIxData {
+    pub contributor_bump: u8,
+}
+
+impl DataLen for CloseContributorIxData {
+    const LEN: usize = 1;
+}
+
+impl IxData for CloseContributorIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self { contributor_bump: reader.read_u8()? })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u8(self.contributor_bump);
+    }
+}
+
+/// Closes a contributor account once the maker claimed the funds, returning
+/// the rent to whoever paid it. The last contributor to close also closes the
+/// fundraiser, returning its rent to the maker.
+pub fn process_close_contributor(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [contributor, maker, fundraiser, contributor_acc, rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !contributor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !contributor_acc.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let ix_data = CloseContributorIxData::unpack(data)?;
+
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    let contributor_state = unsafe {
+        load_acc_mut::<Contributor>(contributor_acc.borrow_mut_data_unchecked())?
+    };
+    if contributor_state.owner != *contributor.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    // Closing a position of another campaign would undercount this one
+    Contributor::check_address(
+        contributor_acc.key(),
+        fundraiser.key(),
+        contributor.key(),
+        ix_data.contributor_bump
+    )?;
+    if fundraiser_state.maker != *maker.key() {
+        return Err(FundraiserError::InvalidMaker.into());
+    }
+    if !fundraiser_state.settled.get() {
+        return Err(FundraiserError::FundraiserNotSettled.into());
+    }
+
+    let rent_payer = find_rent_payer(&contributor_state.rent_payer, contributor, rest)?;
+    unsafe {
+        *rent_payer.borrow_mut_lamports_unchecked() += *contributor_acc.borrow_lamports_unchecked();
+    }
+    contributor_acc.close()?;
+
+    fundraiser_state.contributor_count -= 1;
+    if fundraiser_state.contributor_count.get() == 0 {
+        close_fundraiser(maker, fundraiser)?;
+    }
+    Ok(())
+}
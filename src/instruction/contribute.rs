@@ -2,31 +2,73 @@ use pinocchio::{
     account_info::AccountInfo,
     instruction::{ Seed, Signer },
     program_error::ProgramError,
+    pubkey::Pubkey,
     sysvars::{ clock::Clock, rent::Rent, Sysvar },
     ProgramResult,
 };
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::{ instructions::TransferChecked, state::{ Mint, TokenAccount } };
+use pinocchio_token::{ instructions::{ MintTo, TransferChecked }, state::{ Mint, TokenAccount } };
 
 use crate::{
-    constants::{ MAX_CONTRIBUTION_PERCENTAGE, PERCENTAGE_SCALER, SECONDS_TO_DAYS },
+    constants::{ MAX_MERKLE_PROOF_LEN, NO_TIER },
     error::FundraiserError,
-    state::{ Contributor, Fundraiser },
-    utils::{ load_acc_mut, load_acc_mut_unchecked, load_ix_data, DataLen },
+    event::{ Contributed, Event },
+    merkle,
+    state::{ Contributor, Fundraiser, Round },
+    utils::{ load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ unpack_ix_data, IxData, WireReader, WireWriter },
 };
 
-#[repr(C)]
+/// Wire layout (43 bytes): `amount` (8), `fundraiser_bump` (1),
+/// `contributor_bump` (1), `tier` (1), `beneficiary` (32). The allowlist cap
+/// and proof may follow.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct ContributeIxData {
     pub amount: u64,
     pub fundraiser_bump: u8,
     pub contributor_bump: u8,
+    // Tier to back, `NO_TIER` to keep the current selection (if any)
+    pub tier: u8,
+    // Wallet credited with the contribution, the signer itself for a regular
+    // contribution. The contributor PDA and receipts belong to the beneficiary.
+    pub beneficiary: Pubkey,
 }
 
 impl DataLen for ContributeIxData {
-    const LEN: usize = core::mem::size_of::<ContributeIxData>();
+    const LEN: usize = 43;
 }
 
+impl IxData for ContributeIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let ix_data = Self {
+            amount: reader.read_u64()?,
+            fundraiser_bump: reader.read_u8()?,
+            contributor_bump: reader.read_u8()?,
+            tier: reader.read_u8()?,
+            beneficiary: reader.read_bytes()?,
+        };
+        if ix_data.amount == 0 {
+            return Err(FundraiserError::InvalidAmount.into());
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u64(self.amount);
+        writer.write_u8(self.fundraiser_bump);
+        writer.write_u8(self.contributor_bump);
+        writer.write_u8(self.tier);
+        writer.write_bytes(&self.beneficiary);
+    }
+}
+
+/// Transfers `amount` from the signer into the vault and credits it to
+/// `beneficiary`, which may be another wallet (gift or custodial contributions)
+/// that then co-signs as an extra account, as the gift counts towards its tier
+/// and cap. A fee payer can be passed as another extra signer to fund the
+/// contributor account rent instead of the contributor.
 pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
     let [
         contributor,
@@ -37,6 +79,9 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         vault,
         _token_program,
         _system_program,
+        receipt_mint,
+        contributor_receipt_ata,
+        rest @ ..,
     ] = accounts else {
         return Err(ProgramError::InvalidAccountData);
     };
@@ -57,21 +102,37 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         assert_eq!(contributor_ata_acc.owner(), contributor.key());
     }
 
-    let ix_data = unsafe { load_ix_data::<ContributeIxData>(data)? };
+    // Allowlisted campaigns append the contributor cap and proof to the data
+    let (ix_data, allowlist_data) = unpack_ix_data::<ContributeIxData>(data)?;
+    let beneficiary = &ix_data.beneficiary;
+    if
+        beneficiary != contributor.key() &&
+        !rest.iter().any(|account| account.key() == beneficiary && account.is_signer())
+    {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
 
     // Create contributor account if it doesn't exist
-    if contributor_acc.data_is_empty() || !contributor_acc.is_owned_by(&crate::ID) {
+    let is_new_contributor =
+        contributor_acc.data_is_empty() || !contributor_acc.is_owned_by(&crate::ID);
+    if is_new_contributor {
+        // The round account is never a signer and the beneficiary is skipped,
+        // so the fee payer is unambiguous
+        let rent_payer = rest
+            .iter()
+            .find(|account| account.is_signer() && account.key() != beneficiary)
+            .unwrap_or(contributor);
         let rent = Rent::get()?;
         let pda_bump_bytes = [ix_data.contributor_bump];
         let signer_seeds = [
             Seed::from(Contributor::SEED.as_bytes()),
             Seed::from(fundraiser.key().as_ref()),
-            Seed::from(contributor.key().as_ref()),
+            Seed::from(beneficiary.as_ref()),
             Seed::from(&pda_bump_bytes[..]),
         ];
         let contributor_signer = Signer::from(&signer_seeds[..]);
         (CreateAccount {
-            from: &contributor.clone(),
+            from: rent_payer,
             to: contributor_acc,
             lamports: rent.minimum_balance(Contributor::LEN),
             space: Contributor::LEN as u64,
@@ -80,7 +141,7 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         let contributor_state = (unsafe {
             load_acc_mut_unchecked::<Contributor>(contributor_acc.borrow_mut_data_unchecked())
         })?;
-        contributor_state.initialize(ix_data.amount);
+        contributor_state.initialize(*beneficiary, *rent_payer.key());
     }
     let mint_state = Mint::from_account_info(mint_to_raise)?;
     let decimals = mint_state.decimals();
@@ -91,36 +152,43 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     let contributor_state = unsafe {
         load_acc_mut::<Contributor>(contributor_acc.borrow_mut_data_unchecked())?
     };
-    // Check if the amount to contribute meets the minimum amount required
-    if ix_data.amount < ((10_u32).pow(decimals as u32) as u64) {
-        return Err(FundraiserError::ContributionTooSmall.into());
+    if contributor_state.owner != *beneficiary {
+        return Err(ProgramError::InvalidAccountData);
     }
-
-    // Check if the amount to contribute is less than the maximum allowed contribution
-    if
-        ix_data.amount >
-        (fundraiser_state.amount_to_raise * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER
-    {
-        return Err(FundraiserError::ContributionTooBig.into());
+    // New accounts were created with the fundraiser seeds above
+    if !is_new_contributor {
+        Contributor::check_address(
+            contributor_acc.key(),
+            fundraiser.key(),
+            beneficiary,
+            ix_data.contributor_bump
+        )?;
     }
-
-    // Check if the fundraising duration has been reached
-    let current_time = Clock::get()?.unix_timestamp;
-    if
-        fundraiser_state.duration <
-        (((current_time - fundraiser_state.time_started) / SECONDS_TO_DAYS) as u8)
-    {
-        return Err(FundraiserError::FundraiserEnded.into());
+    if fundraiser_state.uses_registry() {
+        return Err(FundraiserError::RegistryMode.into());
     }
-    // Check if the maximum contributions per contributor have been reached
-    if
-        contributor_state.amount >
-            (fundraiser_state.amount_to_raise * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER &&
-        contributor_state.amount + ix_data.amount >
-            (fundraiser_state.amount_to_raise * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER
-    {
-        return Err(FundraiserError::MaximumContributionsReached.into());
+    // Gifts would let one wallet pose as many backers for the quadratic match
+    if fundraiser_state.is_in_round() && beneficiary != contributor.key() {
+        return Err(FundraiserError::GiftInRound.into());
+    }
+    let current_time = Clock::get()?.unix_timestamp;
+    check_contribution(
+        fundraiser_state,
+        contributor_state.amount.get(),
+        ix_data.amount,
+        decimals,
+        current_time
+    )?;
+
+    if fundraiser_state.is_allowlisted() {
+        check_allowlist(
+            &fundraiser_state.merkle_root,
+            beneficiary,
+            contributor_state.amount.get() + ix_data.amount,
+            allowlist_data
+        )?;
     }
+    select_tier(fundraiser_state, contributor_state, ix_data.tier, ix_data.amount)?;
 
     (TransferChecked {
         from: contributor_ata,
@@ -131,8 +199,186 @@ pub fn process_contribute(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         decimals,
     }).invoke()?; // No invoke_signed here, as the signer is the contributor
 
+    // Mint receipt tokens as a transferable proof of backing
+    mint_receipts(
+        fundraiser_state,
+        fundraiser,
+        receipt_mint,
+        contributor_receipt_ata,
+        beneficiary,
+        ix_data.amount
+    )?;
+
+    // Campaigns in a quadratic funding round report every contribution
+    // made while the round is running
+    if fundraiser_state.is_in_round() {
+        let [round, ..] = rest else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if *round.key() != fundraiser_state.round {
+            return Err(FundraiserError::InvalidRound.into());
+        }
+        let round_state = unsafe { load_acc_mut::<Round>(round.borrow_mut_data_unchecked())? };
+        if current_time < round_state.end_time.get() {
+            round_state
+                .track_contribution(
+                    fundraiser.key(),
+                    contributor_state.amount.get(),
+                    ix_data.amount
+                )
+                .ok_or(FundraiserError::InvalidRound)?;
+        }
+    }
+
     // Update the states
+    let elapsed = current_time - fundraiser_state.time_started.get();
+    let weight = fundraiser_state.weight(ix_data.amount, elapsed);
     contributor_state.amount += ix_data.amount;
+    contributor_state.weight += weight;
     fundraiser_state.current_amount += ix_data.amount;
+    fundraiser_state.total_weight += weight;
+    fundraiser_state.metadata_locked.set(true);
+    if is_new_contributor {
+        fundraiser_state.contributor_count += 1;
+    }
+
+    (Contributed {
+        fundraiser: *fundraiser.key(),
+        contributor: *beneficiary,
+        amount: ix_data.amount,
+        current_amount: fundraiser_state.current_amount.get(),
+    }).emit();
+    Ok(())
+}
+
+/// Checks shared by every contribution mode: campaign still open, amount
+/// within bounds, and the contributor total under the per-wallet cap
+pub(crate) fn check_contribution(
+    fundraiser_state: &Fundraiser,
+    previous_amount: u64,
+    amount: u64,
+    decimals: u8,
+    current_time: i64
+) -> ProgramResult {
+    if fundraiser_state.settled.get() {
+        return Err(FundraiserError::FundraiserSettled.into());
+    }
+    // Check if the amount to contribute meets the minimum amount required
+    if amount < ((10_u32).pow(decimals as u32) as u64) {
+        return Err(FundraiserError::ContributionTooSmall.into());
+    }
+
+    // Check if the amount to contribute is less than the maximum allowed contribution
+    let max_contribution = fundraiser_state.max_contribution();
+    if amount > max_contribution {
+        return Err(FundraiserError::ContributionTooBig.into());
+    }
+
+    // Check if the fundraising duration has been reached
+    if !fundraiser_state.is_open(current_time) {
+        return Err(FundraiserError::FundraiserEnded.into());
+    }
+    check_wallet_cap(fundraiser_state, previous_amount, amount)
+}
+
+/// Per-wallet cap: a wallet at or under `Fundraiser::max_contribution` may
+/// still add one more amount
+fn check_wallet_cap(
+    fundraiser_state: &Fundraiser,
+    previous_amount: u64,
+    amount: u64
+) -> ProgramResult {
+    let max_contribution = fundraiser_state.max_contribution();
+    if previous_amount > max_contribution && previous_amount + amount > max_contribution {
+        return Err(FundraiserError::MaximumContributionsReached.into());
+    }
+    Ok(())
+}
+
+/// Mints the receipt tokens owed for `amount` to `owner`, signed by the
+/// fundraiser. Fundraisers without a receipt mint skip it.
+pub(crate) fn mint_receipts(
+    fundraiser_state: &Fundraiser,
+    fundraiser: &AccountInfo,
+    receipt_mint: &AccountInfo,
+    receipt_ata: &AccountInfo,
+    owner: &Pubkey,
+    amount: u64
+) -> ProgramResult {
+    if !fundraiser_state.has_receipts() {
+        return Ok(());
+    }
+    if
+        fundraiser_state.receipt_mint != *receipt_mint.key() ||
+        TokenAccount::from_account_info(receipt_ata)?.owner() != owner
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let bump_seed = [fundraiser_state.bump];
+    let fundraiser_seeds = [
+        Seed::from(Fundraiser::SEED.as_bytes()),
+        Seed::from(fundraiser_state.maker.as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    (MintTo {
+        mint: receipt_mint,
+        account: receipt_ata,
+        mint_authority: fundraiser,
+        amount: fundraiser_state.receipt_amount(amount),
+    }).invoke_signed(&[Signer::from(&fundraiser_seeds[..])])
+}
+
+/// Validates the requested tier against the fundraiser tiers and records
+/// the selection on the contributor. A tier is picked once, on the first
+/// contribution that names it, and later contributions must keep it.
+fn select_tier(
+    fundraiser_state: &mut Fundraiser,
+    contributor_state: &mut Contributor,
+    tier: u8,
+    amount: u64
+) -> Result<(), FundraiserError> {
+    let tier = if tier == NO_TIER { contributor_state.tier } else { tier };
+    if tier == NO_TIER {
+        return Ok(());
+    }
+    if contributor_state.has_tier() && contributor_state.tier != tier {
+        return Err(FundraiserError::InvalidTier);
+    }
+
+    let is_new_backer = !contributor_state.has_tier();
+    let tier_state = fundraiser_state.tier_mut(tier).ok_or(FundraiserError::InvalidTier)?;
+    if contributor_state.amount.get() + amount < tier_state.min_amount.get() {
+        return Err(FundraiserError::TierMinimumNotMet);
+    }
+    if is_new_backer {
+        if tier_state.is_full() {
+            return Err(FundraiserError::TierFull);
+        }
+        tier_state.backers += 1;
+        contributor_state.tier = tier;
+    }
+    Ok(())
+}
+
+/// Verifies the contributor against the fundraiser allowlist.
+/// `allowlist_data` is the contributor cap (u64, 0 for uncapped) followed
+/// by the proof nodes.
+pub(crate) fn check_allowlist(
+    merkle_root: &[u8; 32],
+    contributor: &Pubkey,
+    new_amount: u64,
+    allowlist_data: &[u8]
+) -> ProgramResult {
+    if allowlist_data.len() < 8 || allowlist_data.len() > 8 + 32 * MAX_MERKLE_PROOF_LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (cap, proof) = allowlist_data.split_at(8);
+    let cap = u64::from_le_bytes(cap.try_into().unwrap());
+    if !merkle::verify(proof, merkle_root, merkle::leaf(contributor, cap)) {
+        return Err(FundraiserError::NotAllowlisted.into());
+    }
+    if cap != 0 && new_amount > cap {
+        return Err(FundraiserError::AllowlistCapExceeded.into());
+    }
     Ok(())
 }
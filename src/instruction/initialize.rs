@@ -6,24 +6,139 @@ use pinocchio::{
     ProgramResult,
 };
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::state::TokenAccount;
-use crate::{ state::Fundraiser, utils::{ load_acc_mut_unchecked, load_ix_data, DataLen } };
+use pinocchio_token::{ instructions::InitializeMint2, state::{ Mint, TokenAccount } };
+use crate::{
+    constants::{ MAX_BONUS_PERIODS, MAX_DURATION_DAYS, MAX_TIERS, MIN_AMOUNT_TO_RAISE },
+    error::FundraiserError,
+    event::{ Event, FundraiserCreated },
+    instruction::metadata::{ create_metadata, MetadataIxData },
+    state::{ BonusPeriod, Fundraiser },
+    utils::{ load_acc_mut_unchecked, DataLen },
+    wire::{ unpack_ix_data, IxData, WireReader, WireWriter },
+};
 
-#[repr(C)]
+/// Wire layout (138 bytes):
+///
+/// | offset | size | field           |
+/// |--------|------|-----------------|
+/// | 0      | 8    | amount          |
+/// | 8      | 1    | duration        |
+/// | 9      | 1    | bump            |
+/// | 10     | 1    | tier_count      |
+/// | 11     | 56   | tiers (4 x 14)  |
+/// | 67     | 8    | receipt_rate    |
+/// | 75     | 1    | receipt_bump    |
+/// | 76     | 32   | merkle_root     |
+/// | 108    | 30   | bonus_periods (3 x 10) |
+///
+/// Optional metadata (`MetadataIxData`) may follow.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct InitializeIxData {
-    pub amount: u64, //8 bytes
-    pub duration: u8, //4 bytes
-    pub bump: u8, //4 bytes
+    // Target in base units, at least `MIN_AMOUNT_TO_RAISE`
+    pub amount: u64,
+    // Campaign length in days, from one to `MAX_DURATION_DAYS`
+    pub duration: u8,
+    pub bump: u8,
+    pub tier_count: u8,
+    pub tiers: [TierIxData; MAX_TIERS],
+    // Receipt tokens per raised token, scaled by `RECEIPT_RATE_SCALER`
+    pub receipt_rate: u64,
+    pub receipt_bump: u8,
+    // Allowlist root for private rounds, all zeroes for open campaigns
+    pub merkle_root: [u8; 32],
+    // Early-bird bonus schedule, unused periods are zeroed
+    pub bonus_periods: [BonusPeriod; MAX_BONUS_PERIODS],
+}
+
+/// Wire layout: `min_amount` (8), `max_backers` (4), `label_id` (2)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TierIxData {
+    pub min_amount: u64,
+    pub max_backers: u32,
+    pub label_id: u16,
+}
+
+impl TierIxData {
+    pub const LEN: usize = 14;
 }
 
 impl DataLen for InitializeIxData {
-    const LEN: usize = core::mem::size_of::<InitializeIxData>();
+    const LEN: usize = 11 + TierIxData::LEN * MAX_TIERS + 9 + 32 + 10 * MAX_BONUS_PERIODS;
+}
+
+impl IxData for InitializeIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let mut ix_data = Self {
+            amount: reader.read_u64()?,
+            duration: reader.read_u8()?,
+            bump: reader.read_u8()?,
+            tier_count: reader.read_u8()?,
+            ..Self::default()
+        };
+        for tier in ix_data.tiers.iter_mut() {
+            tier.min_amount = reader.read_u64()?;
+            tier.max_backers = reader.read_u32()?;
+            tier.label_id = reader.read_u16()?;
+        }
+        ix_data.receipt_rate = reader.read_u64()?;
+        ix_data.receipt_bump = reader.read_u8()?;
+        ix_data.merkle_root = reader.read_bytes()?;
+        for period in ix_data.bonus_periods.iter_mut() {
+            period.duration = reader.read_i64()?.into();
+            period.bonus_bps = reader.read_u16()?.into();
+        }
+
+        if ix_data.amount < MIN_AMOUNT_TO_RAISE {
+            return Err(FundraiserError::InvalidAmount.into());
+        }
+        if ix_data.duration == 0 || ix_data.duration > MAX_DURATION_DAYS {
+            return Err(FundraiserError::InvalidDuration.into());
+        }
+        if (ix_data.tier_count as usize) > MAX_TIERS {
+            return Err(FundraiserError::InvalidTier.into());
+        }
+        if ix_data.receipt_rate == 0 {
+            return Err(FundraiserError::InvalidReceiptRate.into());
+        }
+        if !Fundraiser::is_valid_bonus_schedule(&ix_data.bonus_periods) {
+            return Err(FundraiserError::InvalidBonusSchedule.into());
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u64(self.amount);
+        writer.write_u8(self.duration);
+        writer.write_u8(self.bump);
+        writer.write_u8(self.tier_count);
+        for tier in &self.tiers {
+            writer.write_u64(tier.min_amount);
+            writer.write_u32(tier.max_backers);
+            writer.write_u16(tier.label_id);
+        }
+        writer.write_u64(self.receipt_rate);
+        writer.write_u8(self.receipt_bump);
+        writer.write_bytes(&self.merkle_root);
+        for period in &self.bonus_periods {
+            writer.write_i64(period.duration.get());
+            writer.write_u16(period.bonus_bps.get());
+        }
+    }
 }
 
 pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
-    let [maker, mint_to_raise, fundraiser, vault, _system_program, _token_program, _rest @ ..] =
-        accounts else {
+    let [
+        maker,
+        mint_to_raise,
+        fundraiser,
+        vault,
+        receipt_mint,
+        _system_program,
+        _token_program,
+        rest @ ..,
+    ] = accounts else {
         return Err(ProgramError::InvalidAccountData);
     };
     if !maker.is_signer() {
@@ -32,13 +147,39 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
     if !fundraiser.data_is_empty() {
         return Err(ProgramError::AccountAlreadyInitialized);
     }
-    let vault_acc = TokenAccount::from_account_info(vault)?;
-    // The vault should be intialised on client side to save CUs
-    assert_eq!(vault_acc.owner(), fundraiser.key());
+    if !mint_to_raise.is_owned_by(&pinocchio_token::ID) {
+        return Err(FundraiserError::InvalidMintOwner.into());
+    }
+    let decimals = {
+        let mint_state = Mint::from_account_info(mint_to_raise)?;
+        if !mint_state.is_initialized() {
+            return Err(FundraiserError::MintNotInitialized.into());
+        }
+        mint_state.decimals()
+    };
+    {
+        let vault_acc = TokenAccount::from_account_info(vault)?;
+        // The vault should be intialised on client side to save CUs
+        if vault_acc.owner() != fundraiser.key() {
+            return Err(FundraiserError::InvalidVaultOwner.into());
+        }
+        if vault_acc.mint() != mint_to_raise.key() {
+            return Err(FundraiserError::InvalidVaultMint.into());
+        }
+    }
 
     // Rent can be here too, I guess if it saves CU
     let rent = Rent::get()?;
-    let ix_data = unsafe { load_ix_data::<InitializeIxData>(data)? };
+    // Metadata is optional and appended after the initialize data
+    let (ix_data, metadata_data) = unpack_ix_data::<InitializeIxData>(data)?;
+    // The decoder only knows the unscaled minimum, the target is in base units
+    let min_amount = (10_u64)
+        .checked_pow(decimals as u32)
+        .and_then(|scale| scale.checked_mul(MIN_AMOUNT_TO_RAISE))
+        .ok_or(FundraiserError::InvalidAmount)?;
+    if ix_data.amount < min_amount {
+        return Err(FundraiserError::InvalidAmount.into());
+    }
 
     let bump_seed = [ix_data.bump];
     let fundraiser_seeds = [
@@ -69,5 +210,67 @@ pub fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResul
         ix_data.bump,
         Clock::get()?.unix_timestamp
     );
+    for tier in &ix_data.tiers[..ix_data.tier_count as usize] {
+        fundraiser_state.add_tier(tier.min_amount, tier.max_backers, tier.label_id);
+    }
+
+    create_receipt_mint(maker, fundraiser, receipt_mint, decimals, ix_data.receipt_bump, &rent)?;
+    fundraiser_state.set_receipt_mint(
+        *receipt_mint.key(),
+        ix_data.receipt_rate,
+        ix_data.receipt_bump
+    );
+    fundraiser_state.merkle_root = ix_data.merkle_root;
+    fundraiser_state.bonus_periods = ix_data.bonus_periods;
+
+    if !metadata_data.is_empty() {
+        let [metadata, ..] = rest else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let metadata_ix_data = MetadataIxData::unpack(metadata_data)?;
+        create_metadata(maker, fundraiser, metadata, &metadata_ix_data)?;
+    }
+
+    (FundraiserCreated {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        mint_to_raise: *mint_to_raise.key(),
+        amount_to_raise: ix_data.amount,
+        time_started: fundraiser_state.time_started.get(),
+        duration: ix_data.duration,
+    }).emit();
     Ok(())
 }
+
+/// Creates the receipt mint PDA with the fundraiser as mint authority.
+/// Receipts share the decimals of the raised mint.
+fn create_receipt_mint(
+    maker: &AccountInfo,
+    fundraiser: &AccountInfo,
+    receipt_mint: &AccountInfo,
+    decimals: u8,
+    bump: u8,
+    rent: &Rent
+) -> ProgramResult {
+    let bump_seed = [bump];
+    let receipt_seeds = [
+        Seed::from(Fundraiser::RECEIPT_SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    let receipt_signer = Signer::from(&receipt_seeds[..]);
+    (CreateAccount {
+        from: maker,
+        to: receipt_mint,
+        lamports: rent.minimum_balance(Mint::LEN),
+        space: Mint::LEN as u64,
+        owner: &pinocchio_token::ID,
+    }).invoke_signed(&[receipt_signer])?;
+
+    (InitializeMint2 {
+        mint: receipt_mint,
+        decimals,
+        mint_authority: fundraiser.key(),
+        freeze_authority: None,
+    }).invoke()
+}
@@ -0,0 +1,315 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{ Seed, Signer },
+    program_error::ProgramError,
+    sysvars::{ clock::Clock, rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{
+    instructions::{ CloseAccount, TransferChecked },
+    state::{ Mint, TokenAccount },
+};
+
+use crate::{
+    error::FundraiserError,
+    event::{ Event, Matched },
+    state::{ Fundraiser, MatchPledge },
+    utils::{ load_acc, load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (9 bytes): `cap` (8), `bump` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CreateMatchPledgeIxData {
+    // Most the sponsor will match, must be non-zero
+    pub cap: u64,
+    pub bump: u8,
+}
+
+impl DataLen for CreateMatchPledgeIxData {
+    const LEN: usize = 9;
+}
+
+impl IxData for CreateMatchPledgeIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let ix_data = Self {
+            cap: reader.read_u64()?,
+            bump: reader.read_u8()?,
+        };
+        if ix_data.cap == 0 {
+            return Err(FundraiserError::InvalidAmount.into());
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u64(self.cap);
+        writer.write_u8(self.bump);
+    }
+}
+
+/// Escrows `cap` tokens from the sponsor to match contributions 1:1
+pub fn process_create_match_pledge(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [
+        sponsor,
+        mint_to_raise,
+        fundraiser,
+        pledge,
+        pledge_vault,
+        sponsor_ata,
+        _system_program,
+        _token_program,
+        _rest @ ..,
+    ] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !sponsor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if !pledge.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let ix_data = CreateMatchPledgeIxData::unpack(data)?;
+
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser.borrow_data_unchecked())? };
+    if fundraiser_state.mint_to_raise != *mint_to_raise.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Pledges are accepted while contributions are
+    if !fundraiser_state.is_open(Clock::get()?.unix_timestamp) {
+        return Err(FundraiserError::FundraiserEnded.into());
+    }
+    {
+        let pledge_vault_acc = TokenAccount::from_account_info(pledge_vault)?;
+        // The escrow should be initialised on client side to save CUs
+        if
+            pledge_vault_acc.owner() != pledge.key() ||
+            pledge_vault_acc.mint() != mint_to_raise.key()
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let bump_seed = [ix_data.bump];
+    let pledge_seeds = [
+        Seed::from(MatchPledge::SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(sponsor.key().as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    (CreateAccount {
+        from: sponsor,
+        to: pledge,
+        lamports: Rent::get()?.minimum_balance(MatchPledge::LEN),
+        space: MatchPledge::LEN as u64,
+        owner: &crate::ID,
+    }).invoke_signed(&[Signer::from(&pledge_seeds[..])])?;
+
+    let pledge_state = (unsafe {
+        load_acc_mut_unchecked::<MatchPledge>(pledge.borrow_mut_data_unchecked())
+    })?;
+    pledge_state.initialize(
+        *sponsor.key(),
+        *fundraiser.key(),
+        *pledge_vault.key(),
+        ix_data.cap,
+        ix_data.bump
+    );
+
+    let mint_state = Mint::from_account_info(mint_to_raise)?;
+    (TransferChecked {
+        amount: ix_data.cap,
+        from: sponsor_ata,
+        to: pledge_vault,
+        authority: sponsor,
+        mint: mint_to_raise,
+        decimals: mint_state.decimals(),
+    }).invoke()?;
+    Ok(())
+}
+
+/// Moves the matched share of new contributions, net of refunds, from the
+/// pledge escrow into the fundraiser vault and logs a `Matched` event.
+/// Permissionless, meant to be cranked after contributions.
+pub fn process_settle_match(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [mint_to_raise, fundraiser, pledge, pledge_vault, vault, _token_program, _rest @ ..] =
+        accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !fundraiser.is_owned_by(&crate::ID) || !pledge.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    let pledge_state = unsafe { load_acc_mut::<MatchPledge>(pledge.borrow_mut_data_unchecked())? };
+    if
+        pledge_state.fundraiser != *fundraiser.key() ||
+        pledge_state.vault != *pledge_vault.key() ||
+        fundraiser_state.mint_to_raise != *mint_to_raise.key()
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if TokenAccount::from_account_info(vault)?.owner() != fundraiser.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fundraiser_state.settled.get() {
+        return Err(FundraiserError::FundraiserSettled.into());
+    }
+    // Matching after the deadline could turn a failed campaign into a success.
+    // Same window as contributions, so every contribution can still be matched.
+    if !fundraiser_state.is_open(Clock::get()?.unix_timestamp) {
+        return Err(FundraiserError::FundraiserEnded.into());
+    }
+
+    let match_amount = pledge_state.pending_match(fundraiser_state.unmatched_amount());
+    if match_amount == 0 {
+        return Ok(());
+    }
+
+    let bump_seed = [pledge_state.bump];
+    let pledge_seeds = [
+        Seed::from(MatchPledge::SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(pledge_state.sponsor.as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    let mint_state = Mint::from_account_info(mint_to_raise)?;
+    (TransferChecked {
+        amount: match_amount,
+        from: pledge_vault,
+        to: vault,
+        authority: pledge,
+        mint: mint_to_raise,
+        decimals: mint_state.decimals(),
+    }).invoke_signed(&[Signer::from(&pledge_seeds[..])])?;
+
+    pledge_state.matched += match_amount;
+    fundraiser_state.matched_amount += match_amount;
+    fundraiser_state.current_amount += match_amount;
+
+    (Matched {
+        fundraiser: *fundraiser.key(),
+        sponsor: pledge_state.sponsor,
+        amount: match_amount,
+        current_amount: fundraiser_state.current_amount.get(),
+    }).emit();
+    Ok(())
+}
+
+/// Returns unused escrow to the sponsor once the campaign is over. If the
+/// campaign failed, matched funds are pulled back from the fundraiser vault too.
+/// The escrow and the pledge accounts are closed.
+pub fn process_release_match(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [
+        sponsor,
+        mint_to_raise,
+        fundraiser,
+        pledge,
+        pledge_vault,
+        vault,
+        sponsor_ata,
+        _token_program,
+        _rest @ ..,
+    ] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !sponsor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !pledge.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let pledge_state = unsafe { load_acc::<MatchPledge>(pledge.borrow_data_unchecked())? };
+    if
+        pledge_state.sponsor != *sponsor.key() ||
+        pledge_state.fundraiser != *fundraiser.key() ||
+        pledge_state.vault != *pledge_vault.key()
+    {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let mint_state = Mint::from_account_info(mint_to_raise)?;
+
+    // A settled fundraiser was paid out to the maker, only the escrow is left
+    if !fundraiser.data_is_empty() {
+        if !fundraiser.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let fundraiser_state = unsafe {
+            load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+        };
+        if fundraiser_state.mint_to_raise != *mint_to_raise.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !fundraiser_state.settled.get() {
+            if !fundraiser_state.is_ended(Clock::get()?.unix_timestamp) {
+                return Err(FundraiserError::FundraiserNotEnded.into());
+            }
+            let failed =
+                fundraiser_state.current_amount.get() < fundraiser_state.amount_to_raise.get();
+            if failed && pledge_state.matched.get() > 0 {
+                let bump_seed = [fundraiser_state.bump];
+                let fundraiser_seeds = [
+                    Seed::from(Fundraiser::SEED.as_bytes()),
+                    Seed::from(fundraiser_state.maker.as_ref()),
+                    Seed::from(&bump_seed[..]),
+                ];
+                (TransferChecked {
+                    amount: pledge_state.matched.get(),
+                    from: vault,
+                    to: sponsor_ata,
+                    authority: fundraiser,
+                    mint: mint_to_raise,
+                    decimals: mint_state.decimals(),
+                }).invoke_signed(&[Signer::from(&fundraiser_seeds[..])])?;
+
+                fundraiser_state.matched_amount -= pledge_state.matched.get();
+                fundraiser_state.current_amount -= pledge_state.matched.get();
+            }
+        }
+    }
+
+    let bump_seed = [pledge_state.bump];
+    let pledge_seeds = [
+        Seed::from(MatchPledge::SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(sponsor.key().as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    let pledge_signer = Signer::from(&pledge_seeds[..]);
+
+    let unused = TokenAccount::from_account_info(pledge_vault)?.amount();
+    if unused > 0 {
+        (TransferChecked {
+            amount: unused,
+            from: pledge_vault,
+            to: sponsor_ata,
+            authority: pledge,
+            mint: mint_to_raise,
+            decimals: mint_state.decimals(),
+        }).invoke_signed(core::slice::from_ref(&pledge_signer))?;
+    }
+    (CloseAccount {
+        account: pledge_vault,
+        destination: sponsor,
+        authority: pledge,
+    }).invoke_signed(core::slice::from_ref(&pledge_signer))?;
+
+    // Close the pledge account
+    unsafe {
+        *sponsor.borrow_mut_lamports_unchecked() += *pledge.borrow_lamports_unchecked();
+    }
+    pledge.close()?;
+    Ok(())
+}
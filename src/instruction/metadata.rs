@@ -0,0 +1,155 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{ Seed, Signer },
+    program_error::ProgramError,
+    sysvars::{ rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{
+    constants::{ MAX_NAME_LEN, MAX_SYMBOL_LEN, MAX_URI_LEN },
+    error::FundraiserError,
+    state::{ Fundraiser, FundraiserMetadata },
+    utils::{ load_acc, load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (276 bytes): `name` (32), `symbol` (10), `uri` (200),
+/// `content_hash` (32), `category` (1), `bump` (1). Strings are UTF-8,
+/// zero-padded, and rejected with `InvalidMetadata` otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataIxData {
+    pub name: [u8; MAX_NAME_LEN],
+    pub symbol: [u8; MAX_SYMBOL_LEN],
+    pub uri: [u8; MAX_URI_LEN],
+    pub content_hash: [u8; 32],
+    pub category: u8,
+    // Bump of the metadata PDA, only used when the account is created
+    pub bump: u8,
+}
+
+impl DataLen for MetadataIxData {
+    const LEN: usize = MAX_NAME_LEN + MAX_SYMBOL_LEN + MAX_URI_LEN + 32 + 2;
+}
+
+impl IxData for MetadataIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let ix_data = Self {
+            name: reader.read_bytes()?,
+            symbol: reader.read_bytes()?,
+            uri: reader.read_bytes()?,
+            content_hash: reader.read_bytes()?,
+            category: reader.read_u8()?,
+            bump: reader.read_u8()?,
+        };
+
+        if !is_padded_utf8(&ix_data.name) ||
+            !is_padded_utf8(&ix_data.symbol) ||
+            !is_padded_utf8(&ix_data.uri)
+        {
+            return Err(FundraiserError::InvalidMetadata.into());
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_bytes(&self.name);
+        writer.write_bytes(&self.symbol);
+        writer.write_bytes(&self.uri);
+        writer.write_bytes(&self.content_hash);
+        writer.write_u8(self.category);
+        writer.write_u8(self.bump);
+    }
+}
+
+/// UTF-8 text up to the first zero byte, with only zeroes after it
+fn is_padded_utf8(field: &[u8]) -> bool {
+    let len = field
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(field.len());
+    core::str::from_utf8(&field[..len]).is_ok() && field[len..].iter().all(|byte| *byte == 0)
+}
+
+/// Creates the metadata PDA for a fundraiser, paid for by the maker.
+/// Called from `process_initialize` when metadata is supplied.
+pub fn create_metadata(
+    maker: &AccountInfo,
+    fundraiser: &AccountInfo,
+    metadata: &AccountInfo,
+    ix_data: &MetadataIxData
+) -> ProgramResult {
+    if !metadata.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let rent = Rent::get()?;
+    let bump_seed = [ix_data.bump];
+    let metadata_seeds = [
+        Seed::from(FundraiserMetadata::SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    let metadata_signer = Signer::from(&metadata_seeds[..]);
+    (CreateAccount {
+        from: maker,
+        to: metadata,
+        lamports: rent.minimum_balance(FundraiserMetadata::LEN),
+        space: FundraiserMetadata::LEN as u64,
+        owner: &crate::ID,
+    }).invoke_signed(&[metadata_signer])?;
+
+    let metadata_state = (unsafe {
+        load_acc_mut_unchecked::<FundraiserMetadata>(metadata.borrow_mut_data_unchecked())
+    })?;
+    metadata_state.initialize(*fundraiser.key(), ix_data.bump);
+    metadata_state.update(
+        ix_data.name,
+        ix_data.symbol,
+        ix_data.uri,
+        ix_data.content_hash,
+        ix_data.category
+    );
+    Ok(())
+}
+
+pub fn process_update_metadata(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [maker, fundraiser, metadata, _rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !maker.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !metadata.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let ix_data = MetadataIxData::unpack(data)?;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser.borrow_data_unchecked())? };
+    if fundraiser_state.maker != *maker.key() {
+        return Err(FundraiserError::InvalidMaker.into());
+    }
+    // Backers must see the same description they contributed against
+    if fundraiser_state.metadata_locked.get() {
+        return Err(FundraiserError::MetadataLocked.into());
+    }
+
+    let metadata_state = unsafe {
+        load_acc_mut::<FundraiserMetadata>(metadata.borrow_mut_data_unchecked())?
+    };
+    if metadata_state.fundraiser != *fundraiser.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    metadata_state.update(
+        ix_data.name,
+        ix_data.symbol,
+        ix_data.uri,
+        ix_data.content_hash,
+        ix_data.category
+    );
+    Ok(())
+}
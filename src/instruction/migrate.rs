@@ -0,0 +1,130 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::create_program_address,
+    sysvars::{ rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::{
+    state::{
+        legacy::{ ContributorV0, FundraiserV0 },
+        Contributor,
+        Fundraiser,
+    },
+    utils::{ load_acc_mut, load_acc_mut_unchecked, load_acc_unchecked, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (1 byte): `bump` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MigrateIxData {
+    // Contributor PDA bump, unused for fundraisers
+    pub bump: u8,
+}
+
+impl DataLen for MigrateIxData {
+    const LEN: usize = 1;
+}
+
+impl IxData for MigrateIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self { bump: reader.read_u8()? })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u8(self.bump);
+    }
+}
+
+/// Rewrites an account from an earlier layout into the current one,
+/// detected from its length. The payer tops up the rent when the account
+/// grows, rent freed by a smaller layout stays in the account until it is
+/// closed. First layout contributors need their fundraiser migrated first
+/// and the wallet their PDA was derived from as extra accounts.
+pub fn process_migrate(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [payer, account, _system_program, rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !payer.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !account.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let ix_data = MigrateIxData::unpack(data)?;
+
+    match account.data_len() {
+        FundraiserV0::LEN => {
+            let legacy = unsafe {
+                load_acc_unchecked::<FundraiserV0>(account.borrow_data_unchecked())?.clone()
+            };
+            if legacy.is_initialized == 0 {
+                return Err(ProgramError::UninitializedAccount);
+            }
+            resize(payer, account, Fundraiser::LEN)?;
+            let fundraiser_state = unsafe {
+                load_acc_mut_unchecked::<Fundraiser>(account.borrow_mut_data_unchecked())?
+            };
+            legacy.migrate(fundraiser_state);
+        }
+        ContributorV0::LEN => {
+            let [fundraiser, owner, ..] = rest else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let legacy = unsafe {
+                load_acc_unchecked::<ContributorV0>(account.borrow_data_unchecked())?.clone()
+            };
+            if legacy.is_initialized == 0 {
+                return Err(ProgramError::UninitializedAccount);
+            }
+            // The owner is only known from the PDA seeds
+            let bump_seed = [ix_data.bump];
+            let expected = create_program_address(
+                &[
+                    Contributor::SEED.as_bytes(),
+                    fundraiser.key().as_ref(),
+                    owner.key().as_ref(),
+                    &bump_seed[..],
+                ],
+                &crate::ID
+            )?;
+            if expected != *account.key() {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            if !fundraiser.is_owned_by(&crate::ID) {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            let fundraiser_state = unsafe {
+                load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+            };
+
+            resize(payer, account, Contributor::LEN)?;
+            let contributor_state = unsafe {
+                load_acc_mut_unchecked::<Contributor>(account.borrow_mut_data_unchecked())?
+            };
+            legacy.migrate(contributor_state, *owner.key());
+            fundraiser_state.contributor_count += 1;
+        }
+        _ => {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+    Ok(())
+}
+
+/// Resizes `account` to `new_len` bytes, the payer covering any extra rent
+fn resize(payer: &AccountInfo, account: &AccountInfo, new_len: usize) -> ProgramResult {
+    let rent_due = Rent::get()?.minimum_balance(new_len).saturating_sub(account.lamports());
+    if rent_due > 0 {
+        (Transfer {
+            from: payer,
+            to: account,
+            lamports: rent_due,
+        }).invoke()?;
+    }
+    account.realloc(new_len, true)
+}
@@ -4,10 +4,26 @@ pub mod initialize;
 pub mod contribute;
 pub mod checker;
 pub mod refund;
+pub mod metadata;
+pub mod round;
+pub mod match_pledge;
+pub mod position;
+pub mod close;
+pub mod registry;
+pub mod migrate;
+pub mod status;
 pub use initialize::*;
 pub use contribute::*;
 pub use checker::*;
 pub use refund::*;
+pub use metadata::*;
+pub use round::*;
+pub use match_pledge::*;
+pub use position::*;
+pub use close::*;
+pub use registry::*;
+pub use migrate::*;
+pub use status::*;
 
 #[repr(u8)]
 pub enum ProgramInstruction {
@@ -15,6 +31,20 @@ pub enum ProgramInstruction {
     Contribute,
     CheckContribution,
     Refund,
+    UpdateMetadata,
+    InitializeRound,
+    JoinRound,
+    DistributeRound,
+    CreateMatchPledge,
+    SettleMatch,
+    ReleaseMatch,
+    TransferPosition,
+    CloseContributor,
+    InitializeRegistry,
+    ContributeRegistry,
+    RefundRegistry,
+    Migrate,
+    GetStatus,
 }
 
 impl TryFrom<&u8> for ProgramInstruction {
@@ -26,6 +56,20 @@ impl TryFrom<&u8> for ProgramInstruction {
             1 => Ok(ProgramInstruction::Contribute),
             2 => Ok(ProgramInstruction::CheckContribution),
             3 => Ok(ProgramInstruction::Refund),
+            4 => Ok(ProgramInstruction::UpdateMetadata),
+            5 => Ok(ProgramInstruction::InitializeRound),
+            6 => Ok(ProgramInstruction::JoinRound),
+            7 => Ok(ProgramInstruction::DistributeRound),
+            8 => Ok(ProgramInstruction::CreateMatchPledge),
+            9 => Ok(ProgramInstruction::SettleMatch),
+            10 => Ok(ProgramInstruction::ReleaseMatch),
+            11 => Ok(ProgramInstruction::TransferPosition),
+            12 => Ok(ProgramInstruction::CloseContributor),
+            13 => Ok(ProgramInstruction::InitializeRegistry),
+            14 => Ok(ProgramInstruction::ContributeRegistry),
+            15 => Ok(ProgramInstruction::RefundRegistry),
+            16 => Ok(ProgramInstruction::Migrate),
+            17 => Ok(ProgramInstruction::GetStatus),
             _ => Err(ProgramError::InvalidInstructionData),
         }
     }
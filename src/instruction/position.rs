@@ -0,0 +1,255 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{ Seed, Signer },
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{ rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{ instructions::TransferChecked, state::Mint };
+
+use crate::{
+    error::FundraiserError,
+    instruction::{ contribute::check_allowlist, refund::find_rent_payer },
+    state::{ Contributor, Fundraiser },
+    utils::{ load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ unpack_ix_data, IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (42 bytes): `amount` (8), `recipient` (32), `recipient_bump` (1),
+/// `owner_bump` (1). The recipient allowlist cap and proof may follow.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TransferPositionIxData {
+    pub amount: u64,
+    pub recipient: Pubkey,
+    pub recipient_bump: u8,
+    // Bump of the source contributor account
+    pub owner_bump: u8,
+}
+
+impl DataLen for TransferPositionIxData {
+    const LEN: usize = 42;
+}
+
+impl IxData for TransferPositionIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let ix_data = Self {
+            amount: reader.read_u64()?,
+            recipient: reader.read_bytes()?,
+            recipient_bump: reader.read_u8()?,
+            owner_bump: reader.read_u8()?,
+        };
+        if ix_data.amount == 0 {
+            return Err(FundraiserError::InvalidAmount.into());
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u64(self.amount);
+        writer.write_bytes(&self.recipient);
+        writer.write_u8(self.recipient_bump);
+        writer.write_u8(self.owner_bump);
+    }
+}
+
+/// Moves `amount` of a contribution, with its weight and receipts, to the
+/// recipient's contributor PDA. Moving the whole amount closes the source
+/// account, returning its rent to the fee payer that funded it (passed as the
+/// first extra account when it isn't the owner). Allowlisted campaigns append
+/// the recipient cap and proof to the data.
+pub fn process_transfer_position(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [
+        owner,
+        fundraiser,
+        from_contributor_acc,
+        to_contributor_acc,
+        receipt_mint,
+        from_receipt_ata,
+        to_receipt_ata,
+        _system_program,
+        _token_program,
+        rest @ ..,
+    ] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !owner.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !from_contributor_acc.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let (ix_data, allowlist_data) = unpack_ix_data::<TransferPositionIxData>(data)?;
+    if ix_data.recipient == *owner.key() {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    // Both positions must belong to this fundraiser, or a position could be
+    // moved into another campaign and refunded out of its vault
+    Contributor::check_address(
+        from_contributor_acc.key(),
+        fundraiser.key(),
+        owner.key(),
+        ix_data.owner_bump
+    )?;
+    let is_new_contributor =
+        to_contributor_acc.data_is_empty() || !to_contributor_acc.is_owned_by(&crate::ID);
+    if is_new_contributor {
+        // Create the recipient contributor account from the fundraiser seeds
+        let bump_seed = [ix_data.recipient_bump];
+        let signer_seeds = [
+            Seed::from(Contributor::SEED.as_bytes()),
+            Seed::from(fundraiser.key().as_ref()),
+            Seed::from(ix_data.recipient.as_ref()),
+            Seed::from(&bump_seed[..]),
+        ];
+        (CreateAccount {
+            from: owner,
+            to: to_contributor_acc,
+            lamports: Rent::get()?.minimum_balance(Contributor::LEN),
+            space: Contributor::LEN as u64,
+            owner: &crate::ID,
+        }).invoke_signed(&[Signer::from(&signer_seeds[..])])?;
+        let to_state = (unsafe {
+            load_acc_mut_unchecked::<Contributor>(to_contributor_acc.borrow_mut_data_unchecked())
+        })?;
+        to_state.initialize(ix_data.recipient, *owner.key());
+    } else {
+        Contributor::check_address(
+            to_contributor_acc.key(),
+            fundraiser.key(),
+            &ix_data.recipient,
+            ix_data.recipient_bump
+        )?;
+    }
+
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    let from_state = unsafe {
+        load_acc_mut::<Contributor>(from_contributor_acc.borrow_mut_data_unchecked())?
+    };
+    let to_state = unsafe {
+        load_acc_mut::<Contributor>(to_contributor_acc.borrow_mut_data_unchecked())?
+    };
+    if from_state.owner != *owner.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    if to_state.owner != ix_data.recipient {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fundraiser_state.has_receipts() && fundraiser_state.receipt_mint != *receipt_mint.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Round matching is computed from per-contributor totals
+    if fundraiser_state.is_in_round() {
+        return Err(FundraiserError::InvalidRound.into());
+    }
+    if ix_data.amount > from_state.amount.get() {
+        return Err(FundraiserError::InvalidAmount.into());
+    }
+
+    // A transfer can't take the recipient past the per-wallet cap
+    let new_amount = to_state.amount.get() + ix_data.amount;
+    if new_amount > fundraiser_state.max_contribution() {
+        return Err(FundraiserError::MaximumContributionsReached.into());
+    }
+    if fundraiser_state.is_allowlisted() {
+        check_allowlist(
+            &fundraiser_state.merkle_root,
+            &ix_data.recipient,
+            new_amount,
+            allowlist_data
+        )?;
+    }
+    let closes_source = ix_data.amount == from_state.amount.get();
+    move_tier(fundraiser_state, from_state, to_state, ix_data.amount, closes_source)?;
+
+    // Receipts follow the position so the recipient can refund it
+    let remaining = from_state.amount.get() - ix_data.amount;
+    let receipt_amount =
+        fundraiser_state.receipt_amount(from_state.amount.get()) -
+        fundraiser_state.receipt_amount(remaining);
+    if receipt_amount > 0 {
+        let receipt_mint_state = Mint::from_account_info(receipt_mint)?;
+        (TransferChecked {
+            amount: receipt_amount,
+            from: from_receipt_ata,
+            to: to_receipt_ata,
+            authority: owner,
+            mint: receipt_mint,
+            decimals: receipt_mint_state.decimals(),
+        }).invoke()?;
+    }
+
+    let weight = from_state.weight_share(ix_data.amount);
+    from_state.amount.set(remaining);
+    from_state.weight -= weight;
+    to_state.amount.set(new_amount);
+    to_state.weight += weight;
+    if is_new_contributor {
+        fundraiser_state.contributor_count += 1;
+    }
+
+    if closes_source {
+        let rent_payer = find_rent_payer(&from_state.rent_payer, owner, rest)?;
+        unsafe {
+            *rent_payer.borrow_mut_lamports_unchecked() +=
+                *from_contributor_acc.borrow_lamports_unchecked();
+        }
+        from_contributor_acc.close()?;
+        fundraiser_state.contributor_count -= 1;
+    }
+    Ok(())
+}
+
+/// Carries the source tier over to the recipient. A recipient without a tier
+/// inherits it, taking over the backer slot when the whole position moves.
+fn move_tier(
+    fundraiser_state: &mut Fundraiser,
+    from_state: &Contributor,
+    to_state: &mut Contributor,
+    amount: u64,
+    closes_source: bool
+) -> Result<(), FundraiserError> {
+    if !from_state.has_tier() {
+        return Ok(());
+    }
+    if to_state.has_tier() && to_state.tier != from_state.tier {
+        return Err(FundraiserError::InvalidTier);
+    }
+
+    let is_new_backer = !to_state.has_tier();
+    let tier_state = fundraiser_state
+        .tier_mut(from_state.tier)
+        .ok_or(FundraiserError::InvalidTier)?;
+    let remaining = from_state.amount.get() - amount;
+    if
+        to_state.amount.get() + amount < tier_state.min_amount.get() ||
+        (!closes_source && remaining < tier_state.min_amount.get())
+    {
+        return Err(FundraiserError::TierMinimumNotMet);
+    }
+    match (is_new_backer, closes_source) {
+        // The recipient takes over the source slot
+        (true, true) => {}
+        (true, false) => {
+            if tier_state.is_full() {
+                return Err(FundraiserError::TierFull);
+            }
+            tier_state.backers += 1;
+        }
+        // Two backers of the same tier merge into one
+        (false, true) => {
+            tier_state.backers -= 1;
+        }
+        (false, false) => {}
+    }
+    to_state.tier = from_state.tier;
+    Ok(())
+}
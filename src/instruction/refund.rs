@@ -2,21 +2,44 @@ use pinocchio::{
     account_info::AccountInfo,
     instruction::{ Seed, Signer },
     program_error::ProgramError,
+    pubkey::Pubkey,
     sysvars::{ clock::Clock, Sysvar },
     ProgramResult,
 };
-use pinocchio_token::{ instructions::TransferChecked, state::{ Mint, TokenAccount } };
+use pinocchio_token::{ instructions::{ Burn, TransferChecked }, state::{ Mint, TokenAccount } };
 
 use crate::{
-    constants::SECONDS_TO_DAYS,
     error::FundraiserError,
+    event::{ Event, Refunded },
     state::{ Contributor, Fundraiser },
-    utils::load_acc_mut,
+    utils::{ load_acc_mut, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
 };
 
-pub fn process_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+/// Wire layout (1 byte): `contributor_bump` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RefundIxData {
+    pub contributor_bump: u8,
+}
+
+impl DataLen for RefundIxData {
+    const LEN: usize = 1;
+}
+
+impl IxData for RefundIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self { contributor_bump: reader.read_u8()? })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u8(self.contributor_bump);
+    }
+}
+
+pub fn process_refund(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
     let [
-        contributer,
+        contributor,
         maker,
         mint_to_raise,
         fundraiser,
@@ -25,20 +48,24 @@ pub fn process_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
         vault,
         _system_program,
         _token_program,
-        _rest @ ..,
+        receipt_mint,
+        contributor_receipt_ata,
+        rest @ ..,
     ] = accounts else {
         return Err(ProgramError::InvalidAccountData);
     };
 
-    if !contributer.is_signer() {
+    if !contributor.is_signer() {
         return Err(ProgramError::MissingRequiredSignature);
     }
+    let ix_data = RefundIxData::unpack(data)?;
 
     // / Some checks for authorities
     let vault_acc = TokenAccount::from_account_info(vault)?;
     // The vault should be intialised on client side to save CUs
     assert_eq!(vault_acc.owner(), fundraiser.key());
-    // assert_eq!(contributor_ata_acc.owner(), contributor.key());
+    let contributor_ata_acc = TokenAccount::from_account_info(contributor_ata)?;
+    assert_eq!(contributor_ata_acc.owner(), contributor.key());
     // Some checks for authorities
     // Check if the fundraiser is initialized
     let fundraiser_state = unsafe {
@@ -48,19 +75,31 @@ pub fn process_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
     let contributor_state = unsafe {
         load_acc_mut::<Contributor>(contributor_acc.borrow_mut_data_unchecked())?
     };
+    // Only the beneficiary can refund, whoever paid for the contribution
+    if contributor_state.owner != *contributor.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    // A contributor account of another fundraiser must not drain this vault
+    Contributor::check_address(
+        contributor_acc.key(),
+        fundraiser.key(),
+        contributor.key(),
+        ix_data.contributor_bump
+    )?;
 
     // Check if the fundraising duration has been reached
     let current_time = Clock::get()?.unix_timestamp;
-    if
-        fundraiser_state.duration >
-        (((current_time - fundraiser_state.time_started) / SECONDS_TO_DAYS) as u8)
-    {
+    if !fundraiser_state.is_refund_window(current_time) {
         return Err(FundraiserError::FundraiserNotEnded.into());
     }
-    if vault_acc.amount() >= fundraiser_state.amount_to_raise {
+    if vault_acc.amount() >= fundraiser_state.amount_to_raise.get() {
         return Err(FundraiserError::TargetMet.into());
     }
 
+    // Receipts must be burned before the contribution is returned
+    let amount = contributor_state.amount.get();
+    burn_receipts(fundraiser_state, receipt_mint, contributor_receipt_ata, contributor, amount)?;
+
     // Transfer the funds to the contributor
     let mint_state = Mint::from_account_info(mint_to_raise)?;
     let bump_seed = [fundraiser_state.bump];
@@ -72,19 +111,77 @@ pub fn process_refund(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
 
     let fundraiser_signer = Signer::from(&fundraiser_seeds[..]);
     (TransferChecked {
-        amount: contributor_state.amount,
+        amount,
         from: vault,
         to: contributor_ata,
         authority: fundraiser,
         mint: mint_to_raise,
         decimals: mint_state.decimals(),
-    }).invoke_signed(&[fundraiser_signer.clone()])?;
+    }).invoke_signed(core::slice::from_ref(&fundraiser_signer))?;
 
-    // Close the contributor account
+    // The refunded contribution leaves the totals and frees its tier slot
+    fundraiser_state.current_amount -= amount;
+    fundraiser_state.total_weight -= contributor_state.weight.get();
+    if contributor_state.has_tier() {
+        let tier_state = fundraiser_state
+            .tier_mut(contributor_state.tier)
+            .ok_or(FundraiserError::InvalidTier)?;
+        tier_state.backers -= 1;
+    }
+
+    // Close the contributor account, the rent goes back to whoever paid it
+    let rent_payer = find_rent_payer(&contributor_state.rent_payer, contributor, rest)?;
     unsafe {
-        *contributer.borrow_mut_lamports_unchecked() +=
+        *rent_payer.borrow_mut_lamports_unchecked() +=
             *contributor_acc.borrow_mut_lamports_unchecked();
     }
     contributor_acc.close()?;
+    fundraiser_state.contributor_count -= 1;
+
+    (Refunded {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount,
+    }).emit();
     Ok(())
 }
+
+/// Burns the receipt tokens minted for `amount`. Fundraisers without a
+/// receipt mint skip it.
+pub(crate) fn burn_receipts(
+    fundraiser_state: &Fundraiser,
+    receipt_mint: &AccountInfo,
+    receipt_ata: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64
+) -> ProgramResult {
+    if !fundraiser_state.has_receipts() {
+        return Ok(());
+    }
+    if fundraiser_state.receipt_mint != *receipt_mint.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    (Burn {
+        account: receipt_ata,
+        mint: receipt_mint,
+        authority,
+        amount: fundraiser_state.receipt_amount(amount),
+    }).invoke()
+}
+
+/// Resolves the account to return the contributor account rent to: the
+/// signer itself, or a separate fee payer passed as the first extra account
+pub(crate) fn find_rent_payer<'a>(
+    rent_payer: &Pubkey,
+    signer: &'a AccountInfo,
+    rest: &'a [AccountInfo]
+) -> Result<&'a AccountInfo, ProgramError> {
+    if signer.key() == rent_payer {
+        return Ok(signer);
+    }
+    match rest.first() {
+        Some(account) if account.key() == rent_payer => Ok(account),
+        Some(_) => Err(ProgramError::InvalidAccountData),
+        None => Err(ProgramError::NotEnoughAccountKeys),
+    }
+}
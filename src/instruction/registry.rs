@@ -0,0 +1,349 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{ Seed, Signer },
+    program_error::ProgramError,
+    sysvars::{ clock::Clock, rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{ instructions::TransferChecked, state::{ Mint, TokenAccount } };
+
+use crate::{
+    error::FundraiserError,
+    event::{ Contributed, Event, Refunded },
+    instruction::{
+        contribute::{ check_allowlist, check_contribution, mint_receipts },
+        refund::burn_receipts,
+    },
+    state::{ ContributorRegistry, Fundraiser, RegistryEntry },
+    pod::PodU64,
+    utils::{ load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ unpack_ix_data, IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (5 bytes): `bump` (1), `capacity` (4)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InitializeRegistryIxData {
+    pub bump: u8,
+    // Number of entries the maker prepays rent for, must be non-zero
+    pub capacity: u32,
+}
+
+impl DataLen for InitializeRegistryIxData {
+    const LEN: usize = 5;
+}
+
+impl IxData for InitializeRegistryIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let ix_data = Self {
+            bump: reader.read_u8()?,
+            capacity: reader.read_u32()?,
+        };
+        if ix_data.capacity == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(ix_data)
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u8(self.bump);
+        writer.write_u32(self.capacity);
+    }
+}
+
+/// Wire layout (8 bytes): `amount` (8). The allowlist cap and proof may follow.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContributeRegistryIxData {
+    pub amount: u64,
+}
+
+impl DataLen for ContributeRegistryIxData {
+    const LEN: usize = 8;
+}
+
+impl IxData for ContributeRegistryIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let amount = reader.read_u64()?;
+        if amount == 0 {
+            return Err(FundraiserError::InvalidAmount.into());
+        }
+        Ok(Self { amount })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u64(self.amount);
+    }
+}
+
+/// Switches a fundraiser to the compact registry, where each backer costs
+/// one registry entry instead of a `Contributor` account. Must happen
+/// before the first contribution. The maker funds the rent of `capacity`
+/// entries up front and gets it back when the registry is closed.
+pub fn process_initialize_registry(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [maker, fundraiser, registry, _system_program, _rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !maker.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if !registry.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    let ix_data = InitializeRegistryIxData::unpack(data)?;
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    if fundraiser_state.maker != *maker.key() {
+        return Err(FundraiserError::InvalidMaker.into());
+    }
+    if
+        fundraiser_state.uses_registry() ||
+        fundraiser_state.current_amount.get() > 0 ||
+        fundraiser_state.contributor_count.get() > 0 ||
+        !fundraiser_state.supports_registry()
+    {
+        return Err(FundraiserError::RegistryNotSupported.into());
+    }
+
+    let bump_seed = [ix_data.bump];
+    let registry_seeds = [
+        Seed::from(ContributorRegistry::SEED.as_bytes()),
+        Seed::from(fundraiser.key().as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    (CreateAccount {
+        from: maker,
+        to: registry,
+        lamports: Rent::get()?.minimum_balance(
+            ContributorRegistry::space(ix_data.capacity as usize)
+        ),
+        space: ContributorRegistry::LEN as u64,
+        owner: &crate::ID,
+    }).invoke_signed(&[Signer::from(&registry_seeds[..])])?;
+
+    let registry_state = (unsafe {
+        load_acc_mut_unchecked::<ContributorRegistry>(registry.borrow_mut_data_unchecked())
+    })?;
+    registry_state.initialize(*fundraiser.key(), ix_data.bump);
+    fundraiser_state.registry = *registry.key();
+    Ok(())
+}
+
+/// Contributes to a registry fundraiser. New backers grow the registry by
+/// one entry out of the rent prepaid by the maker. Allowlisted campaigns append the contributor
+/// cap and proof to the data.
+pub fn process_contribute_registry(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [
+        contributor,
+        mint_to_raise,
+        fundraiser,
+        registry,
+        contributor_ata,
+        vault,
+        _token_program,
+        _system_program,
+        receipt_mint,
+        contributor_receipt_ata,
+        _rest @ ..,
+    ] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !contributor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !registry.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if TokenAccount::from_account_info(vault)?.owner() != fundraiser.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let (ix_data, allowlist_data) = unpack_ix_data::<ContributeRegistryIxData>(data)?;
+
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    if fundraiser_state.registry != *registry.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let lookup = {
+        let (_, entries) = unsafe {
+            ContributorRegistry::load_mut(registry.borrow_mut_data_unchecked())?
+        };
+        ContributorRegistry::find(entries, contributor.key()).map(|index| (
+            index,
+            entries[index].amount.get(),
+        ))
+    };
+    let previous_amount = lookup.map_or(0, |(_, amount)| amount);
+
+    let decimals = Mint::from_account_info(mint_to_raise)?.decimals();
+    let current_time = Clock::get()?.unix_timestamp;
+    check_contribution(fundraiser_state, previous_amount, ix_data.amount, decimals, current_time)?;
+    if fundraiser_state.is_allowlisted() {
+        check_allowlist(
+            &fundraiser_state.merkle_root,
+            contributor.key(),
+            previous_amount + ix_data.amount,
+            allowlist_data
+        )?;
+    }
+
+    (TransferChecked {
+        from: contributor_ata,
+        to: vault,
+        authority: contributor,
+        mint: mint_to_raise,
+        amount: ix_data.amount,
+        decimals,
+    }).invoke()?;
+    mint_receipts(
+        fundraiser_state,
+        fundraiser,
+        receipt_mint,
+        contributor_receipt_ata,
+        contributor.key(),
+        ix_data.amount
+    )?;
+
+    match lookup {
+        Ok((index, _)) => {
+            let (_, entries) = unsafe {
+                ContributorRegistry::load_mut(registry.borrow_mut_data_unchecked())?
+            };
+            entries[index].amount += ix_data.amount;
+        }
+        Err(index) => {
+            // Grow the registry by one entry, covered by the prepaid rent
+            let new_len = registry.data_len() + RegistryEntry::LEN;
+            if registry.lamports() < Rent::get()?.minimum_balance(new_len) {
+                return Err(FundraiserError::RegistryFull.into());
+            }
+            registry.realloc(new_len, false)?;
+            unsafe {
+                ContributorRegistry::insert(
+                    registry.borrow_mut_data_unchecked(),
+                    index,
+                    RegistryEntry {
+                        contributor: *contributor.key(),
+                        amount: PodU64::new(ix_data.amount),
+                    }
+                )?;
+            }
+        }
+    }
+
+    // Registry fundraisers have no bonus schedule, weight equals amount
+    fundraiser_state.current_amount += ix_data.amount;
+    fundraiser_state.total_weight += ix_data.amount;
+    fundraiser_state.metadata_locked.set(true);
+
+    (Contributed {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount: ix_data.amount,
+        current_amount: fundraiser_state.current_amount.get(),
+    }).emit();
+    Ok(())
+}
+
+/// Refunds a registry contributor of a failed campaign, removing their
+/// entry. The freed rent stays in the registry for the maker.
+pub fn process_refund_registry(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [
+        contributor,
+        mint_to_raise,
+        fundraiser,
+        registry,
+        contributor_ata,
+        vault,
+        _token_program,
+        receipt_mint,
+        contributor_receipt_ata,
+        _rest @ ..,
+    ] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !contributor.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !registry.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let vault_acc = TokenAccount::from_account_info(vault)?;
+    if vault_acc.owner() != fundraiser.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if TokenAccount::from_account_info(contributor_ata)?.owner() != contributor.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    if fundraiser_state.registry != *registry.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    // Same window as `Refund`
+    if !fundraiser_state.is_refund_window(Clock::get()?.unix_timestamp) {
+        return Err(FundraiserError::FundraiserNotEnded.into());
+    }
+    if vault_acc.amount() >= fundraiser_state.amount_to_raise.get() {
+        return Err(FundraiserError::TargetMet.into());
+    }
+
+    let (index, amount) = {
+        let (_, entries) = unsafe {
+            ContributorRegistry::load_mut(registry.borrow_mut_data_unchecked())?
+        };
+        let index = ContributorRegistry::find(entries, contributor.key()).map_err(
+            |_| FundraiserError::NotInRegistry
+        )?;
+        (index, entries[index].amount.get())
+    };
+
+    // Receipts must be burned before the contribution is returned
+    burn_receipts(fundraiser_state, receipt_mint, contributor_receipt_ata, contributor, amount)?;
+
+    let mint_state = Mint::from_account_info(mint_to_raise)?;
+    let bump_seed = [fundraiser_state.bump];
+    let fundraiser_seeds = [
+        Seed::from(Fundraiser::SEED.as_bytes()),
+        Seed::from(fundraiser_state.maker.as_ref()),
+        Seed::from(&bump_seed[..]),
+    ];
+    (TransferChecked {
+        amount,
+        from: vault,
+        to: contributor_ata,
+        authority: fundraiser,
+        mint: mint_to_raise,
+        decimals: mint_state.decimals(),
+    }).invoke_signed(&[Signer::from(&fundraiser_seeds[..])])?;
+
+    unsafe {
+        ContributorRegistry::remove(registry.borrow_mut_data_unchecked(), index)?;
+    }
+    registry.realloc(registry.data_len() - RegistryEntry::LEN, false)?;
+
+    fundraiser_state.current_amount -= amount;
+    fundraiser_state.total_weight -= amount;
+
+    (Refunded {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount,
+    }).emit();
+    Ok(())
+}
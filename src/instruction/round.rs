@@ -0,0 +1,237 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{ Seed, Signer },
+    program_error::ProgramError,
+    sysvars::{ clock::Clock, rent::Rent, Sysvar },
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+use pinocchio_token::{ instructions::TransferChecked, state::{ Mint, TokenAccount } };
+
+use crate::{
+    constants::MAX_ROUND_CAMPAIGNS,
+    error::FundraiserError,
+    state::{ Fundraiser, Round },
+    utils::{ load_acc_mut, load_acc_mut_unchecked, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (17 bytes): `id` (8), `end_time` (8), `bump` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InitializeRoundIxData {
+    pub id: u64,
+    pub end_time: i64,
+    pub bump: u8,
+}
+
+impl DataLen for InitializeRoundIxData {
+    const LEN: usize = 17;
+}
+
+impl IxData for InitializeRoundIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            id: reader.read_u64()?,
+            end_time: reader.read_i64()?,
+            bump: reader.read_u8()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u64(self.id);
+        writer.write_i64(self.end_time);
+        writer.write_u8(self.bump);
+    }
+}
+
+/// Wire layout (1 byte): `index` (1)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DistributeRoundIxData {
+    // Index of the campaign in `Round::entries`
+    pub index: u8,
+}
+
+impl DataLen for DistributeRoundIxData {
+    const LEN: usize = 1;
+}
+
+impl IxData for DistributeRoundIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        let index = reader.read_u8()?;
+        if (index as usize) >= MAX_ROUND_CAMPAIGNS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self { index })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u8(self.index);
+    }
+}
+
+/// Creates a quadratic funding round. Sponsors fund the matching pool by
+/// transferring tokens straight into the round vault.
+pub fn process_initialize_round(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [authority, round, mint, vault, _system_program, _rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !round.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    {
+        let vault_acc = TokenAccount::from_account_info(vault)?;
+        // The vault should be initialised on client side to save CUs
+        if vault_acc.owner() != round.key() || vault_acc.mint() != mint.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }
+
+    let ix_data = InitializeRoundIxData::unpack(data)?;
+    if ix_data.end_time <= Clock::get()?.unix_timestamp {
+        return Err(FundraiserError::RoundEnded.into());
+    }
+
+    let id_bytes = ix_data.id.to_le_bytes();
+    let bump_seed = [ix_data.bump];
+    let round_seeds = [
+        Seed::from(Round::SEED.as_bytes()),
+        Seed::from(authority.key().as_ref()),
+        Seed::from(&id_bytes[..]),
+        Seed::from(&bump_seed[..]),
+    ];
+    (CreateAccount {
+        from: authority,
+        to: round,
+        lamports: Rent::get()?.minimum_balance(Round::LEN),
+        space: Round::LEN as u64,
+        owner: &crate::ID,
+    }).invoke_signed(&[Signer::from(&round_seeds[..])])?;
+
+    let round_state = (unsafe {
+        load_acc_mut_unchecked::<Round>(round.borrow_mut_data_unchecked())
+    })?;
+    round_state.initialize(
+        *authority.key(),
+        *mint.key(),
+        *vault.key(),
+        ix_data.id,
+        ix_data.end_time,
+        ix_data.bump
+    );
+    Ok(())
+}
+
+/// Adds a fundraiser to a round. Both the maker and the round authority
+/// must sign, and the fundraiser must not have received contributions yet
+/// so that every contribution is tracked by the round.
+pub fn process_join_round(accounts: &[AccountInfo], _data: &[u8]) -> ProgramResult {
+    let [maker, fundraiser, round, authority, _rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !maker.is_signer() || !authority.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !fundraiser.is_owned_by(&crate::ID) || !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(fundraiser.borrow_mut_data_unchecked())?
+    };
+    let round_state = unsafe { load_acc_mut::<Round>(round.borrow_mut_data_unchecked())? };
+
+    if fundraiser_state.maker != *maker.key() {
+        return Err(FundraiserError::InvalidMaker.into());
+    }
+    if round_state.authority != *authority.key() {
+        return Err(ProgramError::IncorrectAuthority);
+    }
+    if
+        fundraiser_state.is_in_round() ||
+        fundraiser_state.uses_registry() ||
+        fundraiser_state.current_amount.get() > 0 ||
+        fundraiser_state.mint_to_raise != round_state.mint
+    {
+        return Err(FundraiserError::InvalidRound.into());
+    }
+    if Clock::get()?.unix_timestamp >= round_state.end_time.get() {
+        return Err(FundraiserError::RoundEnded.into());
+    }
+    if round_state.is_full() {
+        return Err(FundraiserError::RoundFull.into());
+    }
+
+    round_state.add_entry(*fundraiser.key(), *maker.key(), fundraiser_state.amount_to_raise.get());
+    fundraiser_state.round = *round.key();
+    Ok(())
+}
+
+/// Pays the quadratic match of one successful campaign to its maker.
+/// Permissionless, meant to be cranked once per campaign after the round ends.
+pub fn process_distribute_round(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [round, vault, mint, maker_ata, _token_program, _rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+
+    if !round.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let ix_data = DistributeRoundIxData::unpack(data)?;
+    let round_state = unsafe { load_acc_mut::<Round>(round.borrow_mut_data_unchecked())? };
+    if round_state.vault != *vault.key() || round_state.mint != *mint.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if Clock::get()?.unix_timestamp < round_state.end_time.get() {
+        return Err(FundraiserError::RoundNotEnded.into());
+    }
+    if ix_data.index >= round_state.entry_count {
+        return Err(FundraiserError::InvalidRound.into());
+    }
+
+    // The pool is fixed on the first distribution so every campaign is paid
+    // from the same total
+    if round_state.matching_pool.get() == 0 {
+        round_state.matching_pool.set(TokenAccount::from_account_info(vault)?.amount());
+    }
+
+    let entry = round_state.entries[ix_data.index as usize];
+    if entry.paid.get() {
+        return Err(FundraiserError::MatchAlreadyPaid.into());
+    }
+    if TokenAccount::from_account_info(maker_ata)?.owner() != &entry.maker {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let match_amount = round_state.match_amount(&entry);
+    if match_amount == 0 {
+        return Err(FundraiserError::NotEligibleForMatch.into());
+    }
+
+    let id_bytes = round_state.id.get().to_le_bytes();
+    let bump_seed = [round_state.bump];
+    let round_seeds = [
+        Seed::from(Round::SEED.as_bytes()),
+        Seed::from(round_state.authority.as_ref()),
+        Seed::from(&id_bytes[..]),
+        Seed::from(&bump_seed[..]),
+    ];
+    let mint_state = Mint::from_account_info(mint)?;
+    (TransferChecked {
+        amount: match_amount,
+        from: vault,
+        to: maker_ata,
+        authority: round,
+        mint,
+        decimals: mint_state.decimals(),
+    }).invoke_signed(&[Signer::from(&round_seeds[..])])?;
+
+    round_state.entries[ix_data.index as usize].paid.set(true);
+    Ok(())
+}
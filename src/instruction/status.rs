@@ -0,0 +1,130 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    sysvars::{ clock::Clock, Sysvar },
+    ProgramResult,
+};
+
+use crate::{
+    state::{ CampaignStatus, Contributor, Fundraiser },
+    utils::{ load_acc, DataLen },
+    wire::{ IxData, WireReader, WireWriter },
+};
+
+/// Wire layout (1 byte): `contributor_bump` (1), only read when a
+/// contributor account is passed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GetStatusIxData {
+    pub contributor_bump: u8,
+}
+
+impl DataLen for GetStatusIxData {
+    const LEN: usize = 1;
+}
+
+impl IxData for GetStatusIxData {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self { contributor_bump: reader.read_u8()? })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        WireWriter::new(dst).write_u8(self.contributor_bump);
+    }
+}
+
+/// Return data of `GetStatus`. Wire layout (46 bytes): `status` (1),
+/// `amount_to_raise` (8), `current_amount` (8), `time_started` (8),
+/// `duration` (1), `contributor_count` (4), `contributed` (8), `weight` (8)
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundraiserStatus {
+    pub status: CampaignStatus,
+    pub amount_to_raise: u64,
+    pub current_amount: u64,
+    pub time_started: i64,
+    pub duration: u8,
+    pub contributor_count: u32,
+    // Amount and weight of the contributor account, 0 when none was passed
+    pub contributed: u64,
+    pub weight: u64,
+}
+
+impl DataLen for FundraiserStatus {
+    const LEN: usize = 46;
+}
+
+impl IxData for FundraiserStatus {
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let mut reader = WireReader::new(data, Self::LEN)?;
+        Ok(Self {
+            status: CampaignStatus::try_from(reader.read_u8()?)?,
+            amount_to_raise: reader.read_u64()?,
+            current_amount: reader.read_u64()?,
+            time_started: reader.read_i64()?,
+            duration: reader.read_u8()?,
+            contributor_count: reader.read_u32()?,
+            contributed: reader.read_u64()?,
+            weight: reader.read_u64()?,
+        })
+    }
+
+    fn pack_into(&self, dst: &mut [u8]) {
+        let mut writer = WireWriter::new(dst);
+        writer.write_u8(self.status as u8);
+        writer.write_u64(self.amount_to_raise);
+        writer.write_u64(self.current_amount);
+        writer.write_i64(self.time_started);
+        writer.write_u8(self.duration);
+        writer.write_u32(self.contributor_count);
+        writer.write_u64(self.contributed);
+        writer.write_u64(self.weight);
+    }
+}
+
+/// Read-only view of a fundraiser, and optionally of one of its
+/// contributors, written as `FundraiserStatus` return data for callers.
+/// Registry fundraisers keep contributions in the registry account instead.
+pub fn process_get_status(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let [fundraiser, rest @ ..] = accounts else {
+        return Err(ProgramError::InvalidAccountData);
+    };
+    let ix_data = GetStatusIxData::unpack(data)?;
+
+    if !fundraiser.is_owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser.borrow_data_unchecked())? };
+    let mut status = FundraiserStatus {
+        status: fundraiser_state.status(Clock::get()?.unix_timestamp),
+        amount_to_raise: fundraiser_state.amount_to_raise.get(),
+        current_amount: fundraiser_state.current_amount.get(),
+        time_started: fundraiser_state.time_started.get(),
+        duration: fundraiser_state.duration,
+        contributor_count: fundraiser_state.contributor_count.get(),
+        contributed: 0,
+        weight: 0,
+    };
+
+    if let [contributor_acc, ..] = rest {
+        if !contributor_acc.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let contributor_state = unsafe {
+            load_acc::<Contributor>(contributor_acc.borrow_data_unchecked())?
+        };
+        Contributor::check_address(
+            contributor_acc.key(),
+            fundraiser.key(),
+            &contributor_state.owner,
+            ix_data.contributor_bump
+        )?;
+        status.contributed = contributor_state.amount.get();
+        status.weight = contributor_state.weight.get();
+    }
+
+    let mut return_data = [0; FundraiserStatus::LEN];
+    status.pack_into(&mut return_data);
+    set_return_data(&return_data);
+    Ok(())
+}
@@ -6,9 +6,16 @@ extern crate std;
 #[cfg(not(feature = "no-entrypoint"))]
 
 mod entrypoint;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod error;
+pub mod event;
 pub mod instruction;
+pub mod merkle;
+pub mod pod;
 pub mod state;
 pub mod utils;
+pub mod wire;
 pub mod constants;
+pub mod cpi;
 pinocchio_pubkey::declare_id!("AvvaLMBjGBWNamh1qV72gzG412kiZWVFHu2PMi36Bg3G");
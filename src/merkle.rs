@@ -0,0 +1,65 @@
+//! Allowlist proofs for private rounds.
+//!
+//! Leaves commit to a contributor key and an optional per-address cap
+//! (`0` means uncapped). Pairs are hashed in sorted order so proofs do not
+//! need direction bits, and leaves and nodes use distinct prefixes so a node
+//! can never be passed off as a leaf.
+
+use pinocchio::pubkey::Pubkey;
+
+pub const LEAF_PREFIX: u8 = 0;
+pub const NODE_PREFIX: u8 = 1;
+
+/// sha256 over the concatenation of `vals`
+pub fn hashv(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+
+    #[cfg(target_os = "solana")]
+    unsafe {
+        pinocchio::syscalls::sol_sha256(
+            vals as *const _ as *const u8,
+            vals.len() as u64,
+            hash.as_mut_ptr()
+        );
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha2::{ Digest, Sha256 };
+        let mut hasher = Sha256::new();
+        for val in vals {
+            hasher.update(val);
+        }
+        hash.copy_from_slice(&hasher.finalize());
+    }
+
+    hash
+}
+
+pub fn leaf(contributor: &Pubkey, cap: u64) -> [u8; 32] {
+    hashv(&[&[LEAF_PREFIX], contributor.as_ref(), &cap.to_le_bytes()])
+}
+
+pub fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[&[NODE_PREFIX], a, b])
+    } else {
+        hashv(&[&[NODE_PREFIX], b, a])
+    }
+}
+
+/// Checks `leaf` against `root` using `proof`, a packed list of 32-byte
+/// sibling hashes ordered from the leaf up.
+pub fn verify(proof: &[u8], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let siblings = proof.chunks_exact(32);
+    if !siblings.remainder().is_empty() {
+        return false;
+    }
+    let mut computed = leaf;
+    for sibling in siblings {
+        // chunks_exact guarantees 32 bytes
+        let sibling: &[u8; 32] = sibling.try_into().unwrap();
+        computed = node(&computed, sibling);
+    }
+    computed == *root
+}
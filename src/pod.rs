@@ -0,0 +1,81 @@
+//! Alignment-1 integer types for zero-copy account state. Values are stored
+//! as little-endian byte arrays so state structs have no padding and can be
+//! cast from any byte offset.
+
+use core::ops::{ AddAssign, SubAssign };
+
+macro_rules! pod_int {
+    ($name:ident, $int:ty) => {
+        #[repr(transparent)]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name([u8; core::mem::size_of::<$int>()]);
+
+        impl $name {
+            pub const fn new(value: $int) -> Self {
+                Self(value.to_le_bytes())
+            }
+
+            pub const fn get(&self) -> $int {
+                <$int>::from_le_bytes(self.0)
+            }
+
+            pub fn set(&mut self, value: $int) {
+                self.0 = value.to_le_bytes();
+            }
+        }
+
+        impl From<$int> for $name {
+            fn from(value: $int) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for $int {
+            fn from(value: $name) -> Self {
+                value.get()
+            }
+        }
+
+        impl AddAssign<$int> for $name {
+            fn add_assign(&mut self, rhs: $int) {
+                self.set(self.get() + rhs);
+            }
+        }
+
+        impl SubAssign<$int> for $name {
+            fn sub_assign(&mut self, rhs: $int) {
+                self.set(self.get() - rhs);
+            }
+        }
+    };
+}
+
+pod_int!(PodU16, u16);
+pod_int!(PodU32, u32);
+pod_int!(PodU64, u64);
+pod_int!(PodI64, i64);
+
+/// A `bool` stored as a byte, any non-zero value is `true`
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PodBool(u8);
+
+impl PodBool {
+    pub const fn new(value: bool) -> Self {
+        Self(value as u8)
+    }
+
+    pub const fn get(&self) -> bool {
+        self.0 != 0
+    }
+
+    pub fn set(&mut self, value: bool) {
+        self.0 = value as u8;
+    }
+}
+
+impl From<bool> for PodBool {
+    fn from(value: bool) -> Self {
+        Self::new(value)
+    }
+}
@@ -1,27 +1,90 @@
-use crate::utils::{ DataLen, Initialized };
+use pinocchio::{ program_error::ProgramError, pubkey::{ create_program_address, Pubkey } };
 
+use crate::{
+    constants::NO_TIER,
+    pod::PodU64,
+    state::AccountDiscriminator,
+    utils::{ DataLen, Discriminator },
+};
+
+/// Contributor state, seeds `["contributor", fundraiser, owner]`
 #[repr(C)]
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Contributor {
-    pub is_initialized: bool,
-    pub amount: u64,
+    discriminator: u8,
+    version: u8,
+    // Beneficiary credited with the contribution, the only one who can refund it
+    pub owner: Pubkey,
+    // Account that funded the rent, refunded when the account is closed
+    pub rent_payer: Pubkey,
+    pub amount: PodU64,
+    // Index into `Fundraiser::tiers`, `NO_TIER` if no tier was selected
+    pub tier: u8,
+    // Amount adjusted by the early-bird bonus, used for reward and voting shares
+    pub weight: PodU64,
 }
 
 impl DataLen for Contributor {
     const LEN: usize = core::mem::size_of::<Contributor>();
 }
 
-impl Initialized for Contributor {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
+const _: () = assert!(core::mem::align_of::<Contributor>() == 1);
+const _: () = assert!(Contributor::LEN == 2 + 32 * 2 + 8 + 1 + 8);
+
+impl Discriminator for Contributor {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::Contributor as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
     }
 }
 
 impl Contributor {
     pub const SEED: &'static str = "contributor";
 
-    pub fn initialize(&mut self, amount: u64) {
-        self.is_initialized = true;
-        self.amount = amount;
+    pub fn initialize(&mut self, owner: Pubkey, rent_payer: Pubkey) {
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
+        self.owner = owner;
+        self.rent_payer = rent_payer;
+        self.amount.set(0);
+        self.tier = NO_TIER;
+        self.weight.set(0);
+    }
+
+    /// Checks `address` is the contributor account of `owner` in `fundraiser`.
+    /// Contributor accounts do not record their fundraiser, only the seeds tie them.
+    pub fn check_address(
+        address: &Pubkey,
+        fundraiser: &Pubkey,
+        owner: &Pubkey,
+        bump: u8
+    ) -> Result<(), ProgramError> {
+        let bump_seed = [bump];
+        let expected = create_program_address(
+            &[Self::SEED.as_bytes(), fundraiser.as_ref(), owner.as_ref(), &bump_seed[..]],
+            &crate::ID
+        )?;
+        if expected != *address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(())
+    }
+
+    pub fn has_tier(&self) -> bool {
+        self.tier != NO_TIER
+    }
+
+    /// Part of the weight backing `amount` of the contribution
+    pub fn weight_share(&self, amount: u64) -> u64 {
+        if amount == self.amount.get() {
+            return self.weight.get();
+        }
+        (((self.weight.get() as u128) * (amount as u128)) / (self.amount.get() as u128)) as u64
     }
 }
@@ -1,32 +1,151 @@
-use pinocchio::pubkey::Pubkey;
+use pinocchio::{ program_error::ProgramError, pubkey::Pubkey };
 
-use crate::utils::{ DataLen, Initialized };
+use crate::{
+    constants::{
+        BONUS_BPS_SCALER,
+        MAX_BONUS_PERIODS,
+        MAX_CONTRIBUTION_PERCENTAGE,
+        MAX_TIERS,
+        PERCENTAGE_SCALER,
+        RECEIPT_RATE_SCALER,
+        SECONDS_TO_DAYS,
+    },
+    pod::{ PodBool, PodI64, PodU16, PodU32, PodU64 },
+    state::AccountDiscriminator,
+    utils::{ DataLen, Discriminator },
+};
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tier {
+    pub min_amount: PodU64,
+    // 0 means the tier has no backer cap
+    pub max_backers: PodU32,
+    pub backers: PodU32,
+    pub label_id: PodU16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BonusPeriod {
+    // Seconds after the start during which the bonus applies, 0 if unused
+    pub duration: PodI64,
+    pub bonus_bps: PodU16,
+}
+
+const _: () = assert!(core::mem::size_of::<Tier>() == 18);
+const _: () = assert!(core::mem::size_of::<BonusPeriod>() == 10);
+
+impl Tier {
+    pub fn is_full(&self) -> bool {
+        self.max_backers.get() != 0 && self.backers.get() >= self.max_backers.get()
+    }
+}
+
+/// Lifecycle of a fundraiser as seen by the program at a given time
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CampaignStatus {
+    // The start time is still ahead, only possible with clock drift
+    NotStarted = 0,
+    // Accepting contributions
+    Active,
+    // Target met, the maker can claim the funds
+    Succeeded,
+    // Ended below the target, contributors can claim refunds
+    Failed,
+    // Funds claimed by the maker
+    Finalized,
+    // Reserved, the program has no way to cancel a fundraiser yet
+    Cancelled,
+}
+
+impl TryFrom<u8> for CampaignStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(CampaignStatus::NotStarted),
+            1 => Ok(CampaignStatus::Active),
+            2 => Ok(CampaignStatus::Succeeded),
+            3 => Ok(CampaignStatus::Failed),
+            4 => Ok(CampaignStatus::Finalized),
+            5 => Ok(CampaignStatus::Cancelled),
+            _ => Err(ProgramError::InvalidArgument),
+        }
+    }
+}
+
+/// Fundraiser state. Every field has an alignment of 1, so the account can
+/// be cast from any offset and carries no padding.
 #[repr(C)]
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Fundraiser {
-    is_initialized: bool,
+    discriminator: u8,
+    version: u8,
     pub maker: Pubkey,
     pub mint_to_raise: Pubkey,
-    pub amount_to_raise: u64,
-    pub current_amount: u64,
-    pub time_started: i64,
+    pub amount_to_raise: PodU64,
+    pub current_amount: PodU64,
+    pub time_started: PodI64,
     pub duration: u8,
     pub bump: u8,
+    pub tier_count: u8,
+    pub tiers: [Tier; MAX_TIERS],
+    pub receipt_mint: Pubkey,
+    // Receipt tokens per raised token, scaled by `RECEIPT_RATE_SCALER`
+    pub receipt_rate: PodU64,
+    pub receipt_bump: u8,
+    // Root of the contributor allowlist, all zeroes for open campaigns
+    pub merkle_root: [u8; 32],
+    // Early-bird bonus schedule, ordered by increasing duration
+    pub bonus_periods: [BonusPeriod; MAX_BONUS_PERIODS],
+    // Sum of all contributor weights
+    pub total_weight: PodU64,
+    // Quadratic funding round the fundraiser joined, all zeroes if none
+    pub round: Pubkey,
+    // Part of `current_amount` that came from sponsor match pledges
+    pub matched_amount: PodU64,
+    // Set once the maker claimed the funds, the account lives on until the
+    // last contributor account is closed
+    pub settled: PodBool,
+    // Number of open contributor accounts
+    pub contributor_count: PodU32,
+    // Compact contributor registry, all zeroes when contributions are
+    // tracked in `Contributor` accounts
+    pub registry: Pubkey,
+    // Set by the first contribution and never cleared, not even by refunds,
+    // so the metadata stays what backers contributed against
+    pub metadata_locked: PodBool,
 }
 
 impl DataLen for Fundraiser {
     const LEN: usize = core::mem::size_of::<Fundraiser>();
 }
 
-impl Initialized for Fundraiser {
-    fn is_initialized(&self) -> bool {
-        self.is_initialized
+const _: () = assert!(core::mem::align_of::<Fundraiser>() == 1);
+const _: () = assert!(
+    Fundraiser::LEN ==
+        2 + 32 * 2 + 8 * 3 + 3 + 18 * MAX_TIERS + 32 + 8 + 1 + 32 + 10 * MAX_BONUS_PERIODS + 8 +
+            32 + 8 + 1 + 4 + 32 + 1
+);
+
+impl Discriminator for Fundraiser {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::Fundraiser as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
     }
 }
 
 impl Fundraiser {
     pub const SEED: &'static str = "fundraiser";
+    pub const RECEIPT_SEED: &'static str = "receipt";
 
     pub fn initialize(
         &mut self,
@@ -37,13 +156,148 @@ impl Fundraiser {
         bump: u8,
         time_started: i64
     ) {
-        self.is_initialized = true;
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
         self.maker = maker;
         self.mint_to_raise = mint_to_raise;
-        self.amount_to_raise = amount_to_raise;
-        self.current_amount = 0;
-        self.time_started = time_started;
+        self.amount_to_raise.set(amount_to_raise);
+        self.current_amount.set(0);
+        self.total_weight.set(0);
+        self.settled.set(false);
+        self.contributor_count.set(0);
+        self.time_started.set(time_started);
         self.duration = duration;
         self.bump = bump;
+        self.tier_count = 0;
+    }
+
+    /// Whole days elapsed at `now`
+    pub fn elapsed_days(&self, now: i64) -> i64 {
+        (now - self.time_started.get()) / SECONDS_TO_DAYS
+    }
+
+    /// Whether contributions are still accepted at `now`, through the whole
+    /// last day
+    pub fn is_open(&self, now: i64) -> bool {
+        self.elapsed_days(now) <= (self.duration as i64)
+    }
+
+    /// Whether the fundraising period is over at `now`, the complement of `is_open`
+    pub fn is_ended(&self, now: i64) -> bool {
+        !self.is_open(now)
+    }
+
+    /// Whether contributors of a campaign that missed its target can claim
+    /// refunds at `now`. The window opens on the last day, which overlaps
+    /// with `is_open`.
+    pub fn is_refund_window(&self, now: i64) -> bool {
+        self.elapsed_days(now) >= (self.duration as i64)
+    }
+
+    pub fn is_target_met(&self) -> bool {
+        self.current_amount.get() >= self.amount_to_raise.get()
+    }
+
+    pub fn status(&self, now: i64) -> CampaignStatus {
+        if self.settled.get() {
+            CampaignStatus::Finalized
+        } else if now < self.time_started.get() {
+            CampaignStatus::NotStarted
+        } else if self.is_target_met() {
+            // The maker can claim as soon as the target is met
+            CampaignStatus::Succeeded
+        } else if self.is_open(now) {
+            CampaignStatus::Active
+        } else {
+            CampaignStatus::Failed
+        }
+    }
+
+    /// Largest single contribution, and the per-wallet total past which a
+    /// wallet cannot contribute again
+    pub fn max_contribution(&self) -> u64 {
+        (self.amount_to_raise.get() * MAX_CONTRIBUTION_PERCENTAGE) / PERCENTAGE_SCALER
+    }
+
+    /// Contributions no pledge has matched yet. Pledges share this pool so
+    /// they never match the same contribution twice.
+    pub fn unmatched_amount(&self) -> u64 {
+        let contributed = self.current_amount.get() - self.matched_amount.get();
+        contributed.saturating_sub(self.matched_amount.get())
+    }
+
+    pub fn set_receipt_mint(&mut self, receipt_mint: Pubkey, receipt_rate: u64, receipt_bump: u8) {
+        self.receipt_mint = receipt_mint;
+        self.receipt_rate.set(receipt_rate);
+        self.receipt_bump = receipt_bump;
+    }
+
+    /// Receipt tokens owed for `amount` raised tokens
+    pub fn receipt_amount(&self, amount: u64) -> u64 {
+        let receipt_rate = self.receipt_rate.get() as u128;
+        (((amount as u128) * receipt_rate) / (RECEIPT_RATE_SCALER as u128)) as u64
+    }
+
+    /// Fundraisers migrated from the first layout have no receipt mint
+    pub fn has_receipts(&self) -> bool {
+        self.receipt_mint != [0; 32]
+    }
+
+    pub fn is_allowlisted(&self) -> bool {
+        self.merkle_root != [0; 32]
+    }
+
+    /// Checks the bonus periods are ordered with any unused ones at the end
+    pub fn is_valid_bonus_schedule(bonus_periods: &[BonusPeriod]) -> bool {
+        bonus_periods.windows(2).all(|pair| {
+            let (first, second) = (pair[0].duration.get(), pair[1].duration.get());
+            second == 0 || (first != 0 && first < second)
+        })
+    }
+
+    /// Bonus in basis points for a contribution made `elapsed` seconds after the start
+    pub fn bonus_bps(&self, elapsed: i64) -> u16 {
+        self.bonus_periods
+            .iter()
+            .take_while(|period| period.duration.get() != 0)
+            .find(|period| elapsed < period.duration.get())
+            .map_or(0, |period| period.bonus_bps.get())
+    }
+
+    /// Weighted share of `amount` contributed `elapsed` seconds after the start
+    pub fn weight(&self, amount: u64, elapsed: i64) -> u64 {
+        let scale = BONUS_BPS_SCALER + (self.bonus_bps(elapsed) as u64);
+        (((amount as u128) * (scale as u128)) / (BONUS_BPS_SCALER as u128)) as u64
+    }
+
+    pub fn uses_registry(&self) -> bool {
+        self.registry != [0; 32]
+    }
+
+    /// Tiers, bonus weights and rounds need per-contributor state the
+    /// registry does not keep
+    pub fn supports_registry(&self) -> bool {
+        self.tier_count == 0 && self.bonus_periods[0].duration.get() == 0 && !self.is_in_round()
+    }
+
+    pub fn is_in_round(&self) -> bool {
+        self.round != [0; 32]
+    }
+
+    pub fn add_tier(&mut self, min_amount: u64, max_backers: u32, label_id: u16) {
+        self.tiers[self.tier_count as usize] = Tier {
+            min_amount: min_amount.into(),
+            max_backers: max_backers.into(),
+            backers: PodU32::default(),
+            label_id: label_id.into(),
+        };
+        self.tier_count += 1;
+    }
+
+    pub fn tier_mut(&mut self, tier: u8) -> Option<&mut Tier> {
+        if tier >= self.tier_count {
+            return None;
+        }
+        self.tiers.get_mut(tier as usize)
     }
 }
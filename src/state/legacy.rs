@@ -0,0 +1,68 @@
+//! Account layouts written by earlier program versions, kept so live
+//! accounts can be migrated in place with the `Migrate` instruction
+
+use pinocchio::pubkey::Pubkey;
+
+use crate::{ state::{ Contributor, Fundraiser }, utils::DataLen };
+
+/// `Fundraiser` before discriminators were introduced
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FundraiserV0 {
+    // Read as a byte, any non-zero value counts as initialized
+    pub is_initialized: u8,
+    pub maker: Pubkey,
+    pub mint_to_raise: Pubkey,
+    pub amount_to_raise: u64,
+    pub current_amount: u64,
+    pub time_started: i64,
+    pub duration: u8,
+    pub bump: u8,
+}
+
+impl DataLen for FundraiserV0 {
+    const LEN: usize = core::mem::size_of::<FundraiserV0>();
+}
+
+impl FundraiserV0 {
+    /// Rewrites the fundraiser into the current layout. Migrated fundraisers
+    /// have no receipt mint, contributors are counted as they are migrated.
+    pub fn migrate(&self, fundraiser: &mut Fundraiser) {
+        *fundraiser = Fundraiser::default();
+        fundraiser.initialize(
+            self.maker,
+            self.mint_to_raise,
+            self.amount_to_raise,
+            self.duration,
+            self.bump,
+            self.time_started
+        );
+        fundraiser.current_amount.set(self.current_amount);
+        fundraiser.total_weight.set(self.current_amount);
+        fundraiser.metadata_locked.set(self.current_amount > 0);
+    }
+}
+
+/// `Contributor` before discriminators were introduced
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContributorV0 {
+    // Read as a byte, any non-zero value counts as initialized
+    pub is_initialized: u8,
+    pub amount: u64,
+}
+
+impl DataLen for ContributorV0 {
+    const LEN: usize = core::mem::size_of::<ContributorV0>();
+}
+
+impl ContributorV0 {
+    /// Rewrites the contributor into the current layout. The wallet the PDA
+    /// was derived from owns the position and paid its rent.
+    pub fn migrate(&self, contributor: &mut Contributor, owner: Pubkey) {
+        *contributor = Contributor::default();
+        contributor.initialize(owner, owner);
+        contributor.amount.set(self.amount);
+        contributor.weight.set(self.amount);
+    }
+}
@@ -0,0 +1,67 @@
+use pinocchio::pubkey::Pubkey;
+
+use crate::{ pod::PodU64, state::AccountDiscriminator, utils::{ DataLen, Discriminator } };
+
+/// Match pledge state, seeds `["match", fundraiser, sponsor]`
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchPledge {
+    discriminator: u8,
+    version: u8,
+    pub sponsor: Pubkey,
+    pub fundraiser: Pubkey,
+    // Escrow token account owned by the pledge PDA
+    pub vault: Pubkey,
+    pub cap: PodU64,
+    // Amount already moved into the fundraiser vault
+    pub matched: PodU64,
+    pub bump: u8,
+}
+
+impl DataLen for MatchPledge {
+    const LEN: usize = core::mem::size_of::<MatchPledge>();
+}
+
+const _: () = assert!(core::mem::align_of::<MatchPledge>() == 1);
+const _: () = assert!(MatchPledge::LEN == 2 + 32 * 3 + 8 * 2 + 1);
+
+impl Discriminator for MatchPledge {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::MatchPledge as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl MatchPledge {
+    pub const SEED: &'static str = "match";
+
+    pub fn initialize(
+        &mut self,
+        sponsor: Pubkey,
+        fundraiser: Pubkey,
+        vault: Pubkey,
+        cap: u64,
+        bump: u8
+    ) {
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
+        self.sponsor = sponsor;
+        self.fundraiser = fundraiser;
+        self.vault = vault;
+        self.cap.set(cap);
+        self.matched.set(0);
+        self.bump = bump;
+    }
+
+    /// Part of the `unmatched` contributions this pledge covers 1:1, bounded
+    /// by what is left of the cap
+    pub fn pending_match(&self, unmatched: u64) -> u64 {
+        unmatched.min(self.cap.get().saturating_sub(self.matched.get()))
+    }
+}
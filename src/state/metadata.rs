@@ -0,0 +1,64 @@
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    constants::{ MAX_NAME_LEN, MAX_SYMBOL_LEN, MAX_URI_LEN },
+    state::AccountDiscriminator,
+    utils::{ DataLen, Discriminator },
+};
+
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FundraiserMetadata {
+    discriminator: u8,
+    version: u8,
+    pub fundraiser: Pubkey,
+    pub name: [u8; MAX_NAME_LEN],
+    pub symbol: [u8; MAX_SYMBOL_LEN],
+    pub uri: [u8; MAX_URI_LEN],
+    pub content_hash: [u8; 32],
+    pub category: u8,
+    pub bump: u8,
+}
+
+impl DataLen for FundraiserMetadata {
+    const LEN: usize = core::mem::size_of::<FundraiserMetadata>();
+}
+
+impl Discriminator for FundraiserMetadata {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::Metadata as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl FundraiserMetadata {
+    pub const SEED: &'static str = "metadata";
+
+    pub fn initialize(&mut self, fundraiser: Pubkey, bump: u8) {
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
+        self.fundraiser = fundraiser;
+        self.bump = bump;
+    }
+
+    pub fn update(
+        &mut self,
+        name: [u8; MAX_NAME_LEN],
+        symbol: [u8; MAX_SYMBOL_LEN],
+        uri: [u8; MAX_URI_LEN],
+        content_hash: [u8; 32],
+        category: u8
+    ) {
+        self.name = name;
+        self.symbol = symbol;
+        self.uri = uri;
+        self.content_hash = content_hash;
+        self.category = category;
+    }
+}
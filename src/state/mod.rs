@@ -1,4 +1,25 @@
 pub mod fundraiser;
 pub mod contributor;
+pub mod metadata;
+pub mod round;
+pub mod match_pledge;
+pub mod registry;
+pub mod legacy;
 pub use fundraiser::*;
 pub use contributor::*;
+pub use metadata::*;
+pub use round::*;
+pub use match_pledge::*;
+pub use registry::*;
+
+/// Leading type tag of every program account, 0 is left for uninitialized data
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AccountDiscriminator {
+    Fundraiser = 1,
+    Contributor,
+    Metadata,
+    Round,
+    MatchPledge,
+    Registry,
+}
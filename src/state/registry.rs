@@ -0,0 +1,131 @@
+use pinocchio::{ program_error::ProgramError, pubkey::Pubkey };
+
+use crate::{
+    pod::PodU64,
+    state::AccountDiscriminator,
+    utils::{ load_acc_mut, DataLen, Discriminator },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RegistryEntry {
+    pub contributor: Pubkey,
+    pub amount: PodU64,
+}
+
+impl DataLen for RegistryEntry {
+    const LEN: usize = core::mem::size_of::<RegistryEntry>();
+}
+
+const _: () = assert!(core::mem::align_of::<RegistryEntry>() == 1);
+const _: () = assert!(RegistryEntry::LEN == 32 + 8);
+
+/// Header of the compact contributor registry. The account grows by one
+/// `RegistryEntry` per backer, entries follow the header sorted by
+/// contributor key so lookups are a binary search. The maker prepays the
+/// rent of every entry when creating the registry.
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContributorRegistry {
+    discriminator: u8,
+    version: u8,
+    pub bump: u8,
+    pub fundraiser: Pubkey,
+    pub entry_count: PodU64,
+}
+
+impl DataLen for ContributorRegistry {
+    const LEN: usize = core::mem::size_of::<ContributorRegistry>();
+}
+
+const _: () = assert!(core::mem::align_of::<ContributorRegistry>() == 1);
+const _: () = assert!(ContributorRegistry::LEN == 3 + 32 + 8);
+
+impl Discriminator for ContributorRegistry {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::Registry as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl ContributorRegistry {
+    pub const SEED: &'static str = "registry";
+
+    pub fn initialize(&mut self, fundraiser: Pubkey, bump: u8) {
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
+        self.bump = bump;
+        self.fundraiser = fundraiser;
+        self.entry_count = PodU64::new(0);
+    }
+
+    /// Account size holding `entry_count` entries
+    pub const fn space(entry_count: usize) -> usize {
+        Self::LEN + entry_count * RegistryEntry::LEN
+    }
+
+    /// Splits the registry account data into its header and entries
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be the data of an account owned by the program
+    pub unsafe fn load_mut(
+        bytes: &mut [u8]
+    ) -> Result<(&mut Self, &mut [RegistryEntry]), ProgramError> {
+        if bytes.len() < Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, entries) = bytes.split_at_mut(Self::LEN);
+        let header = load_acc_mut::<Self>(header)?;
+        if entries.len() != (header.entry_count.get() as usize) * RegistryEntry::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let entries = core::slice::from_raw_parts_mut(
+            entries.as_mut_ptr() as *mut RegistryEntry,
+            header.entry_count.get() as usize
+        );
+        Ok((header, entries))
+    }
+
+    /// Inserts `entry` at `index`, the account must already have been grown
+    /// by one entry
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be the data of an account owned by the program
+    pub unsafe fn insert(
+        bytes: &mut [u8],
+        index: usize,
+        entry: RegistryEntry
+    ) -> Result<(), ProgramError> {
+        load_acc_mut::<Self>(&mut bytes[..Self::LEN])?.entry_count += 1;
+        let (_, entries) = Self::load_mut(bytes)?;
+        entries.copy_within(index..entries.len() - 1, index + 1);
+        entries[index] = entry;
+        Ok(())
+    }
+
+    /// Removes the entry at `index`, the account should be shrunk by one
+    /// entry afterwards
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must be the data of an account owned by the program
+    pub unsafe fn remove(bytes: &mut [u8], index: usize) -> Result<(), ProgramError> {
+        let (header, entries) = Self::load_mut(bytes)?;
+        entries.copy_within(index + 1.., index);
+        header.entry_count -= 1;
+        Ok(())
+    }
+
+    /// Index of `contributor`, or the index to insert it at to keep the order
+    pub fn find(entries: &[RegistryEntry], contributor: &Pubkey) -> Result<usize, usize> {
+        entries.binary_search_by(|entry| entry.contributor.cmp(contributor))
+    }
+}
@@ -0,0 +1,151 @@
+use pinocchio::pubkey::Pubkey;
+
+use crate::{
+    constants::MAX_ROUND_CAMPAIGNS,
+    pod::{ PodBool, PodI64, PodU64 },
+    state::AccountDiscriminator,
+    utils::{ isqrt, DataLen, Discriminator },
+};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RoundEntry {
+    pub fundraiser: Pubkey,
+    pub maker: Pubkey,
+    pub amount_to_raise: PodU64,
+    // Amount raised while the round was running
+    pub current_amount: PodU64,
+    // Sum over contributors of the square root of their total contribution
+    pub sum_sqrt: PodU64,
+    pub paid: PodBool,
+}
+
+const _: () = assert!(core::mem::size_of::<RoundEntry>() == 32 * 2 + 8 * 3 + 1);
+
+impl RoundEntry {
+    pub fn is_successful(&self) -> bool {
+        self.current_amount.get() >= self.amount_to_raise.get()
+    }
+
+    /// Quadratic funding weight, `(sum of sqrt)^2 - sum of contributions`
+    pub fn match_weight(&self) -> u128 {
+        if !self.is_successful() {
+            return 0;
+        }
+        let sum_sqrt = self.sum_sqrt.get() as u128;
+        (sum_sqrt * sum_sqrt).saturating_sub(self.current_amount.get() as u128)
+    }
+}
+
+/// Round state, seeds `["round", authority, id]`
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Round {
+    discriminator: u8,
+    version: u8,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub id: PodU64,
+    pub end_time: PodI64,
+    // Vault balance snapshotted on the first distribution, 0 before
+    pub matching_pool: PodU64,
+    pub entry_count: u8,
+    pub bump: u8,
+    pub entries: [RoundEntry; MAX_ROUND_CAMPAIGNS],
+}
+
+impl DataLen for Round {
+    const LEN: usize = core::mem::size_of::<Round>();
+}
+
+const _: () = assert!(core::mem::align_of::<Round>() == 1);
+const _: () = assert!(Round::LEN == 2 + 32 * 3 + 8 * 3 + 2 + 89 * MAX_ROUND_CAMPAIGNS);
+
+impl Discriminator for Round {
+    const DISCRIMINATOR: u8 = AccountDiscriminator::Round as u8;
+    const VERSION: u8 = 1;
+
+    fn discriminator(&self) -> u8 {
+        self.discriminator
+    }
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+}
+
+impl Round {
+    pub const SEED: &'static str = "round";
+
+    pub fn initialize(
+        &mut self,
+        authority: Pubkey,
+        mint: Pubkey,
+        vault: Pubkey,
+        id: u64,
+        end_time: i64,
+        bump: u8
+    ) {
+        self.discriminator = Self::DISCRIMINATOR;
+        self.version = Self::VERSION;
+        self.authority = authority;
+        self.mint = mint;
+        self.vault = vault;
+        self.id.set(id);
+        self.end_time.set(end_time);
+        self.matching_pool.set(0);
+        self.entry_count = 0;
+        self.bump = bump;
+    }
+
+    pub fn is_full(&self) -> bool {
+        (self.entry_count as usize) >= MAX_ROUND_CAMPAIGNS
+    }
+
+    pub fn add_entry(&mut self, fundraiser: Pubkey, maker: Pubkey, amount_to_raise: u64) {
+        self.entries[self.entry_count as usize] = RoundEntry {
+            fundraiser,
+            maker,
+            amount_to_raise: amount_to_raise.into(),
+            ..RoundEntry::default()
+        };
+        self.entry_count += 1;
+    }
+
+    pub fn entry_mut(&mut self, fundraiser: &Pubkey) -> Option<&mut RoundEntry> {
+        self.entries[..self.entry_count as usize]
+            .iter_mut()
+            .find(|entry| entry.fundraiser == *fundraiser)
+    }
+
+    /// Records a contribution that took a contributor total from
+    /// `previous_amount` to `previous_amount + amount`
+    pub fn track_contribution(
+        &mut self,
+        fundraiser: &Pubkey,
+        previous_amount: u64,
+        amount: u64
+    ) -> Option<()> {
+        let entry = self.entry_mut(fundraiser)?;
+        entry.sum_sqrt += isqrt(previous_amount + amount) - isqrt(previous_amount);
+        entry.current_amount += amount;
+        Some(())
+    }
+
+    pub fn total_match_weight(&self) -> u128 {
+        self.entries[..self.entry_count as usize]
+            .iter()
+            .map(RoundEntry::match_weight)
+            .sum()
+    }
+
+    /// Share of the matching pool owed to `entry`
+    pub fn match_amount(&self, entry: &RoundEntry) -> u64 {
+        let total_weight = self.total_match_weight();
+        if total_weight == 0 {
+            return 0;
+        }
+        (((self.matching_pool.get() as u128) * entry.match_weight()) / total_weight) as u64
+    }
+}
@@ -1,56 +1,127 @@
 use pinocchio::program_error::ProgramError;
+
+use crate::error::FundraiserError;
+
 pub trait DataLen {
     const LEN: usize;
 }
 
-pub trait Initialized {
-    fn is_initialized(&self) -> bool;
+/// Type tag and layout version leading every program account
+pub trait Discriminator {
+    const DISCRIMINATOR: u8;
+    const VERSION: u8;
+
+    fn discriminator(&self) -> u8;
+    fn version(&self) -> u8;
 }
 
+/// Rejects uninitialized accounts, accounts of another type and accounts
+/// written with another layout version
 #[inline(always)]
-pub unsafe fn load_acc<T: DataLen + Initialized>(bytes: &[u8]) -> Result<&T, ProgramError> {
+pub fn check_discriminator<T: Discriminator>(acc: &T) -> Result<(), ProgramError> {
+    check_account_tag::<T>(acc.discriminator(), acc.version())
+}
+
+/// Same checks on the two leading bytes of the raw account data
+#[inline(always)]
+pub fn check_account_tag<T: Discriminator>(
+    discriminator: u8,
+    version: u8
+) -> Result<(), ProgramError> {
+    match discriminator {
+        0 => Err(ProgramError::UninitializedAccount),
+        discriminator if discriminator != T::DISCRIMINATOR => Err(ProgramError::InvalidAccountData),
+        _ if version != T::VERSION => Err(FundraiserError::InvalidAccountVersion.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Loads an initialized account of type `T`
+///
+/// # Safety
+///
+/// `bytes` must not be mutably aliased while the returned reference lives.
+#[inline(always)]
+pub unsafe fn load_acc<T: DataLen + Discriminator>(bytes: &[u8]) -> Result<&T, ProgramError> {
     load_acc_unchecked::<T>(bytes).and_then(|acc| {
-        if acc.is_initialized() { Ok(acc) } else { Err(ProgramError::UninitializedAccount) }
+        check_discriminator(acc)?;
+        Ok(acc)
     })
 }
 
+/// Loads `T` without checking its discriminator
+///
+/// # Safety
+///
+/// `bytes` must not be mutably aliased while the returned reference lives.
 #[inline(always)]
 pub unsafe fn load_acc_unchecked<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
-    if bytes.len() != T::LEN {
+    // Always holds for the alignment-1 state types
+    if bytes.len() != T::LEN || bytes.as_ptr().align_offset(core::mem::align_of::<T>()) != 0 {
         return Err(ProgramError::InvalidAccountData);
     }
     Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// Mutably loads an initialized account of type `T`
+///
+/// # Safety
+///
+/// `bytes` must not be otherwise borrowed while the returned reference lives.
 #[inline(always)]
-pub unsafe fn load_acc_mut<T: DataLen + Initialized>(
+pub unsafe fn load_acc_mut<T: DataLen + Discriminator>(
     bytes: &mut [u8]
 ) -> Result<&mut T, ProgramError> {
     load_acc_mut_unchecked::<T>(bytes).and_then(|acc| {
-        if acc.is_initialized() { Ok(acc) } else { Err(ProgramError::UninitializedAccount) }
+        check_discriminator(acc)?;
+        Ok(acc)
     })
 }
 
+/// Mutably loads `T` without checking its discriminator
+///
+/// # Safety
+///
+/// `bytes` must not be otherwise borrowed while the returned reference lives.
 #[inline(always)]
 pub unsafe fn load_acc_mut_unchecked<T: DataLen>(bytes: &mut [u8]) -> Result<&mut T, ProgramError> {
-    if bytes.len() != T::LEN {
+    if bytes.len() != T::LEN || bytes.as_ptr().align_offset(core::mem::align_of::<T>()) != 0 {
         return Err(ProgramError::InvalidAccountData);
     }
     Ok(&mut *(bytes.as_mut_ptr() as *mut T))
 }
 
-#[inline(always)]
-pub unsafe fn load_ix_data<T: DataLen>(bytes: &[u8]) -> Result<&T, ProgramError> {
-    if bytes.len() != T::LEN {
-        return Err(ProgramError::InvalidInstructionData);
+/// Integer square root, rounded down
+pub fn isqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method starting from a power of two above the root
+    let mut x = 1u64 << (64 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
     }
-    Ok(&*(bytes.as_ptr() as *const T))
 }
 
+/// Raw bytes of `data`
+///
+/// # Safety
+///
+/// `T` must have no padding, as holds for the alignment-1 state types.
 pub unsafe fn to_bytes<T: DataLen>(data: &T) -> &[u8] {
     core::slice::from_raw_parts(data as *const T as *const u8, T::LEN)
 }
 
+/// Mutable raw bytes of `data`
+///
+/// # Safety
+///
+/// `T` must have no padding and accept any bit pattern written through the
+/// returned slice.
 pub unsafe fn to_mut_bytes<T: DataLen>(data: &mut T) -> &mut [u8] {
     core::slice::from_raw_parts_mut(data as *mut T as *mut u8, T::LEN)
 }
@@ -0,0 +1,114 @@
+//! Explicit wire format for instruction data. Fields are encoded back to
+//! back in declaration order, integers little-endian, with no padding.
+//! Decoders copy every field out and validate it instead of casting the
+//! raw bytes, so any input either decodes to a sane value or is rejected.
+
+use pinocchio::program_error::ProgramError;
+
+use crate::utils::DataLen;
+
+/// Instruction data with a fixed-size wire encoding of `LEN` bytes
+pub trait IxData: DataLen + Sized {
+    /// Decodes exactly `Self::LEN` bytes, rejecting out-of-range values
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError>;
+
+    /// Encodes into the first `Self::LEN` bytes of `dst`, which must be large enough
+    fn pack_into(&self, dst: &mut [u8]);
+
+    #[cfg(feature = "std")]
+    fn pack(&self) -> std::vec::Vec<u8> {
+        let mut data = std::vec![0; Self::LEN];
+        self.pack_into(&mut data);
+        data
+    }
+}
+
+/// Decodes the fixed-size head of `data` and returns the variable-length
+/// tail (allowlist proofs, appended metadata) untouched
+pub fn unpack_ix_data<T: IxData>(data: &[u8]) -> Result<(T, &[u8]), ProgramError> {
+    if data.len() < T::LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (head, tail) = data.split_at(T::LEN);
+    Ok((T::unpack(head)?, tail))
+}
+
+/// Sequential little-endian reader over an instruction data buffer
+pub struct WireReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> WireReader<'a> {
+    /// Starts reading `data`, which must be exactly `len` bytes long
+    pub fn new(data: &'a [u8], len: usize) -> Result<Self, ProgramError> {
+        if data.len() != len {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self { data })
+    }
+
+    pub fn read_bytes<const N: usize>(&mut self) -> Result<[u8; N], ProgramError> {
+        let (head, tail) = self.data
+            .split_first_chunk::<N>()
+            .ok_or(ProgramError::InvalidInstructionData)?;
+        self.data = tail;
+        Ok(*head)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, ProgramError> {
+        self.read_bytes::<1>().map(|bytes| bytes[0])
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, ProgramError> {
+        self.read_bytes().map(u16::from_le_bytes)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, ProgramError> {
+        self.read_bytes().map(u32::from_le_bytes)
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, ProgramError> {
+        self.read_bytes().map(u64::from_le_bytes)
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64, ProgramError> {
+        self.read_bytes().map(i64::from_le_bytes)
+    }
+}
+
+/// Sequential little-endian writer, the counterpart of `WireReader`
+pub struct WireWriter<'a> {
+    data: &'a mut [u8],
+    offset: usize,
+}
+
+impl<'a> WireWriter<'a> {
+    pub fn new(data: &'a mut [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.data[self.offset..self.offset + bytes.len()].copy_from_slice(bytes);
+        self.offset += bytes.len();
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.write_bytes(&[value]);
+    }
+
+    pub fn write_u16(&mut self, value: u16) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_i64(&mut self, value: i64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+}
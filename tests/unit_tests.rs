@@ -1,18 +1,120 @@
-use mollusk_svm::result::{ Check, ProgramResult };
+use mollusk_svm::result::{ Check, InstructionResult, ProgramResult };
 use mollusk_svm::{ program, Mollusk };
+use pinocchio::program_error::ProgramError;
 use solana_sdk::account::{ Account, WritableAccount };
-use solana_sdk::instruction::{ AccountMeta, Instruction };
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::program_option::COption;
 use solana_sdk::program_pack::Pack;
 use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
+use solana_log_collector::LogCollector;
 extern crate alloc;
 use alloc::vec;
 
-use pinocchio_fundraiser::instruction::{ InitializeIxData, ContributeIxData };
-use pinocchio_fundraiser::state::{ Contributor, Fundraiser };
-use pinocchio_fundraiser::utils::to_bytes;
+use pinocchio_fundraiser::client::idl::{ idl, idl_json };
+use pinocchio_fundraiser::client::{
+    find_contributor_address,
+    find_fundraiser_address,
+    find_match_pledge_address,
+    find_metadata_address,
+    find_receipt_mint_address,
+    find_registry_address,
+    find_round_address,
+    get_associated_token_address,
+    parse_log_data,
+    parse_logs,
+    AllowlistProof,
+    CampaignStatus,
+    CheckContribution,
+    CloseContributor,
+    Contribute,
+    ContributeRegistry,
+    ContributorAccount,
+    CreateMatchPledge,
+    DistributeRound,
+    FundraiserAccount,
+    GetStatus,
+    Initialize,
+    InitializeRegistry,
+    InitializeRound,
+    JoinRound,
+    Migrate,
+    Refund,
+    RefundRegistry,
+    ReleaseMatch,
+    SettleMatch,
+    TransferPosition,
+    UpdateMetadata,
+    PROGRAM_ID,
+    TOKEN_PROGRAM_ID,
+};
+use pinocchio_fundraiser::constants::{
+    MAX_BONUS_PERIODS,
+    MAX_DURATION_DAYS,
+    MAX_MERKLE_PROOF_LEN,
+    MAX_NAME_LEN,
+    MAX_ROUND_CAMPAIGNS,
+    MAX_SYMBOL_LEN,
+    MAX_TIERS,
+    MAX_URI_LEN,
+    NO_TIER,
+    RECEIPT_RATE_SCALER,
+    SECONDS_TO_DAYS,
+};
+use pinocchio_fundraiser::instruction as processor;
+use pinocchio_fundraiser::instruction::{
+    InitializeIxData,
+    InitializeRegistryIxData,
+    MigrateIxData,
+    FundraiserStatus,
+    GetStatusIxData,
+    RefundIxData,
+    CloseContributorIxData,
+    ContributeIxData,
+    ContributeRegistryIxData,
+    CreateMatchPledgeIxData,
+    DistributeRoundIxData,
+    InitializeRoundIxData,
+    MetadataIxData,
+    TierIxData,
+    TransferPositionIxData,
+};
+use pinocchio_fundraiser::error::FundraiserError;
+use pinocchio_fundraiser::event::{
+    Cancelled,
+    Contributed,
+    Event,
+    Finalized,
+    FundraiserCreated,
+    FundraiserEvent,
+    Matched,
+    Refunded,
+    MAX_EVENT_LEN,
+};
+use pinocchio_fundraiser::merkle;
+use pinocchio_fundraiser::pod::PodU64;
+use pinocchio_fundraiser::wire::{ unpack_ix_data, IxData };
+use pinocchio_fundraiser::state::legacy::{ ContributorV0, FundraiserV0 };
+use pinocchio_fundraiser::state::{
+    AccountDiscriminator,
+    BonusPeriod,
+    Contributor,
+    ContributorRegistry,
+    Fundraiser,
+    FundraiserMetadata,
+    MatchPledge,
+    RegistryEntry,
+    Round,
+};
+use pinocchio_fundraiser::utils::{
+    isqrt,
+    load_acc,
+    load_acc_mut,
+    load_acc_mut_unchecked,
+    to_bytes,
+    DataLen,
+    Discriminator,
+};
 use solana_sdk::rent::Rent;
 use solana_sdk::sysvar::Sysvar;
 
@@ -32,26 +134,16 @@ pub fn mollusk() -> Mollusk {
         "tests/elfs/spl_token",
         &mollusk_svm::program::loader_keys::LOADER_V3
     );
+    mollusk.logger = Some(LogCollector::new_ref());
     mollusk
 }
 
-pub trait AccountExt {
-    fn refresh(
-        &mut self,
-        account_pubkey: &Pubkey,
-        result: mollusk_svm::result::InstructionResult
-    ) -> &mut Self;
-}
-
-impl AccountExt for Account {
-    fn refresh(
-        &mut self,
-        account_pubkey: &Pubkey,
-        result: mollusk_svm::result::InstructionResult
-    ) -> &mut Self {
-        *self = result.get_account(account_pubkey).unwrap().clone();
-        self
-    }
+/// Events logged by the program since the last call, read back from the
+/// transaction logs the way a client would
+pub fn take_events(mollusk: &mut Mollusk) -> Vec<FundraiserEvent> {
+    let logger = mollusk.logger.replace(LogCollector::new_ref()).unwrap();
+    let logs = logger.borrow();
+    parse_logs(logs.get_recorded_content())
 }
 
 pub fn get_spl_token_program() -> (Pubkey, Account) {
@@ -65,41 +157,79 @@ pub fn get_rent_data() -> Vec<u8> {
     }
 }
 
+/// Keys and accounts of a fundraiser under test
+#[derive(Clone)]
+pub struct FundraiserFixture {
+    pub maker: Pubkey,
+    pub maker_account: Account,
+    pub fundraiser: Pubkey,
+    pub fundraiser_bump: u8,
+    pub fundraiser_account: Account,
+    pub mint_to_raise: Pubkey,
+    pub mint_to_raise_account: Account,
+    pub vault: Pubkey,
+    pub vault_account: Account,
+    pub system_program: Pubkey,
+    pub system_account: Account,
+    pub token_program: Pubkey,
+    pub token_account: Account,
+}
+
+impl FundraiserFixture {
+    /// Take back the accounts written by an instruction
+    pub fn refresh(&mut self, result: &InstructionResult) {
+        for (key, account) in [
+            (self.maker, &mut self.maker_account),
+            (self.fundraiser, &mut self.fundraiser_account),
+            (self.mint_to_raise, &mut self.mint_to_raise_account),
+            (self.vault, &mut self.vault_account),
+        ] {
+            if let Some(updated) = result.get_account(&key) {
+                *account = updated.clone();
+            }
+        }
+    }
+}
+
+/// Keys and accounts of a contributor under test
+#[derive(Clone)]
+pub struct ContributorFixture {
+    pub wallet: Pubkey,
+    pub wallet_account: Account,
+    pub contributor_acc: Pubkey,
+    pub contributor_bump: u8,
+    pub contributor_acc_account: Account,
+    pub ata: Pubkey,
+    pub ata_account: Account,
+}
+
+impl ContributorFixture {
+    /// Take back the accounts written by an instruction
+    pub fn refresh(&mut self, result: &InstructionResult) {
+        for (key, account) in [
+            (self.wallet, &mut self.wallet_account),
+            (self.contributor_acc, &mut self.contributor_acc_account),
+            (self.ata, &mut self.ata_account),
+        ] {
+            if let Some(updated) = result.get_account(&key) {
+                *account = updated.clone();
+            }
+        }
+    }
+}
+
 /// Setup common fundraiser accounts
-pub fn setup_fundraiser(mollusk: &Mollusk) -> (
-    // Pubkeys
-    Pubkey,
-    Pubkey,
-    Pubkey,
-    u8,
-    Pubkey,
-    Pubkey,
-    Pubkey,
-    Pubkey,
-    // Accounts
-    Account,
-    Account,
-    Account,
-    Account,
-    Account,
-    Account,
-    Account,
-) {
+pub fn setup_fundraiser(mollusk: &Mollusk) -> FundraiserFixture {
     // Setup system and token programs
     let (system_program, system_account) = program::keyed_account_for_system_program();
     let (token_program, token_account) = get_spl_token_program();
 
-    // Setup maker and contributor accounts
+    // Setup maker account
     let maker = Pubkey::new_from_array([0x01; 32]);
     let maker_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
-    let contributor = Pubkey::new_unique();
-    let contributor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
 
     // Derive PDAs
-    let (fundraiser, fundraiser_bump) = Pubkey::find_program_address(
-        &[Fundraiser::SEED.as_bytes(), &maker.to_bytes()],
-        &PROGRAM
-    );
+    let (fundraiser, fundraiser_bump) = find_fundraiser_address(&maker);
 
     // Create empty fundraiser account (will be initialized later)
     let fundraiser_account = Account::new(0, 0, &system_program);
@@ -125,7 +255,7 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
         .unwrap();
 
     // Create vault account
-    let vault = Pubkey::new_from_array([0x04; 32]);
+    let vault = get_associated_token_address(&fundraiser, &mint_to_raise);
     let mut vault_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
@@ -147,126 +277,172 @@ pub fn setup_fundraiser(mollusk: &Mollusk) -> (
         )
         .unwrap();
 
-    (
-        // Return Pubkeys
+    FundraiserFixture {
         maker,
-        contributor,
+        maker_account,
         fundraiser,
         fundraiser_bump,
-        mint_to_raise,
-        vault,
-        system_program,
-        token_program,
-        // Return Accounts
-        maker_account,
-        contributor_account,
         fundraiser_account,
+        mint_to_raise,
         mint_to_raise_account,
+        vault,
         vault_account,
+        system_program,
         system_account,
+        token_program,
         token_account,
-    )
+    }
 }
 
 /// Execute the initialize fundraiser instruction
 pub fn execute_initialize(
     mollusk: &Mollusk,
-    maker: Pubkey,
-    maker_account: Account,
-    mint_to_raise: Pubkey,
-    mint_to_raise_account: Account,
+    fixture: &FundraiserFixture,
+    amount: u64,
+    duration: u8
+) -> InstructionResult {
+    // Create instruction data
+    let ix_data = get_initialize_ix_data(
+        fixture.fundraiser,
+        fixture.fundraiser_bump,
+        amount,
+        duration
+    );
+
+    execute_initialize_with_data(mollusk, fixture, ix_data)
+}
+
+/// Get initialize instruction data with a 1:1 receipt rate
+pub fn get_initialize_ix_data(
     fundraiser: Pubkey,
-    fundraiser_account: Account,
-    vault: Pubkey,
-    vault_account: Account,
     fundraiser_bump: u8,
-    system_program: Pubkey,
-    system_account: Account,
-    token_program: Pubkey,
-    token_account: Account,
     amount: u64,
     duration: u8
-) -> mollusk_svm::result::InstructionResult {
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(maker, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, true),
-        AccountMeta::new(vault, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
-
-    // Create instruction data
-    let ix_data = InitializeIxData {
+) -> InitializeIxData {
+    let (_, receipt_bump) = find_receipt_mint_address(&fundraiser);
+    InitializeIxData {
         amount,
         duration,
         bump: fundraiser_bump,
-    };
+        receipt_rate: RECEIPT_RATE_SCALER,
+        receipt_bump,
+        ..Default::default()
+    }
+}
+
+/// Receipt mint account as created by the initialize instruction
+pub fn get_receipt_mint_account(mollusk: &Mollusk, fundraiser: Pubkey, supply: u64) -> Account {
+    let mut receipt_mint_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(spl_token::state::Mint::LEN),
+        spl_token::state::Mint::LEN,
+        &spl_token::ID
+    );
+    solana_sdk::program_pack::Pack
+        ::pack(
+            spl_token::state::Mint {
+                decimals: 6,
+                supply,
+                is_initialized: true,
+                freeze_authority: COption::None,
+                mint_authority: COption::Some(fundraiser),
+            },
+            receipt_mint_account.data_as_mut_slice()
+        )
+        .unwrap();
+    receipt_mint_account
+}
+
+/// Get a receipt token account for the contributor
+pub fn get_receipt_ata(
+    mollusk: &Mollusk,
+    contributor: Pubkey,
+    fundraiser: Pubkey,
+    amount: u64
+) -> (Pubkey, Account) {
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let receipt_ata = get_associated_token_address(&contributor, &receipt_mint);
+    let mut receipt_ata_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &spl_token::ID
+    );
+    solana_sdk::program_pack::Pack
+        ::pack(
+            spl_token::state::Account {
+                amount,
+                mint: receipt_mint,
+                owner: contributor,
+                delegate: COption::None,
+                state: spl_token::state::AccountState::Initialized,
+                close_authority: COption::None,
+                is_native: COption::None,
+                delegated_amount: 0,
+            },
+            receipt_ata_account.data_as_mut_slice()
+        )
+        .unwrap();
+    (receipt_ata, receipt_ata_account)
+}
 
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![0]; // Ix discriminator = 0
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(&ix_data) });
 
-    // Create instruction
-    let instruction = Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts);
+/// Execute the initialize fundraiser instruction with the given instruction data
+pub fn execute_initialize_with_data(
+    mollusk: &Mollusk,
+    fixture: &FundraiserFixture,
+    ix_data: InitializeIxData
+) -> InstructionResult {
+    let (receipt_mint, _) = find_receipt_mint_address(&fixture.fundraiser);
+    let instruction = (Initialize {
+        maker: fixture.maker,
+        mint_to_raise: fixture.mint_to_raise,
+        params: ix_data,
+        metadata: None,
+    }).instruction();
 
     // Create transaction accounts
-    let tx_accounts = &vec![
-        (maker, maker_account),
-        (mint_to_raise, mint_to_raise_account),
-        (fundraiser, fundraiser_account),
-        (vault, vault_account),
-        (system_program, system_account),
-        (token_program, token_account)
+    let tx_accounts = [
+        (fixture.maker, fixture.maker_account.clone()),
+        (fixture.mint_to_raise, fixture.mint_to_raise_account.clone()),
+        (fixture.fundraiser, fixture.fundraiser_account.clone()),
+        (fixture.vault, fixture.vault_account.clone()),
+        (receipt_mint, Account::new(0, 0, &fixture.system_program)),
+        (fixture.system_program, fixture.system_account.clone()),
+        (fixture.token_program, fixture.token_account.clone()),
     ];
 
     // Process instruction
-    let result = mollusk.process_and_validate_instruction(
-        &instruction,
-        tx_accounts,
-        &[Check::success()]
-    );
-    result
-}
-
-/// Get new contributor account
-pub fn get_new_contributor_account(_mollusk: &Mollusk, system_program: Pubkey) -> (Pubkey, Account) {
-    let contributor = Pubkey::new_unique();
-    let contributor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
-    return (contributor, contributor_account);
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()])
 }
 
 /// Setup contributor accounts
 pub fn setup_contributor(
     mollusk: &Mollusk,
-    contributor: Pubkey,
-    fundraiser: Pubkey,
-    mint_to_raise: Pubkey,
-    system_program: Pubkey,
-    token_program: Pubkey
-) -> (Pubkey, u8, Pubkey, Account, Account) {
+    fixture: &FundraiserFixture,
+    contributor: Pubkey
+) -> ContributorFixture {
+    let contributor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &fixture.system_program);
+
     // Derive contributor PDA
-    let (contributor_acc, contributor_bump) = Pubkey::find_program_address(
-        &[Contributor::SEED.as_bytes(), &fundraiser.to_bytes(), &contributor.to_bytes()],
-        &PROGRAM
+    let (contributor_acc, contributor_bump) = find_contributor_address(
+        &fixture.fundraiser,
+        &contributor
     );
 
     // Create empty contributor account (will be initialized during contribute)
-    let contributor_acc_account = Account::new(0, 0, &system_program);
+    let contributor_acc_account = Account::new(0, 0, &fixture.system_program);
 
     // Create contributor ATA
-    let contributor_ata = Pubkey::new_unique();
+    let contributor_ata = get_associated_token_address(&contributor, &fixture.mint_to_raise);
     let mut contributor_ata_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
-        &token_program
+        &fixture.token_program
     );
     solana_sdk::program_pack::Pack
         ::pack(
             spl_token::state::Account {
                 amount: 1_000_000,
-                mint: mint_to_raise,
+                mint: fixture.mint_to_raise,
                 owner: contributor,
                 delegate: COption::None,
                 state: spl_token::state::AccountState::Initialized,
@@ -278,114 +454,113 @@ pub fn setup_contributor(
         )
         .unwrap();
 
-    (
+    ContributorFixture {
+        wallet: contributor,
+        wallet_account: contributor_account,
         contributor_acc,
         contributor_bump,
-        contributor_ata,
         contributor_acc_account,
-        contributor_ata_account,
-    )
+        ata: contributor_ata,
+        ata_account: contributor_ata_account,
+    }
 }
 
 /// Execute the contribute instruction
 pub fn execute_contribute(
     mollusk: &Mollusk,
-    contributor: Pubkey,
-    contributor_account: Account,
-    mint_to_raise: Pubkey,
-    mint_to_raise_account: Account,
-    fundraiser: Pubkey,
-    fundraiser_account: Account,
-    contributor_acc: Pubkey,
-    contributor_acc_account: Account,
-    contributor_ata: Pubkey,
-    contributor_ata_account: Account,
-    vault: Pubkey,
-    vault_account: Account,
-    contributor_bump: u8,
-    fundraiser_bump: u8,
-    system_program: Pubkey,
-    system_account: Account,
-    token_program: Pubkey,
-    token_account: Account,
+    fixture: &FundraiserFixture,
+    contributor: &ContributorFixture,
     amount: u64
-) -> mollusk_svm::result::InstructionResult {
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(contributor, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, false),
-        AccountMeta::new(contributor_acc, true),
-        AccountMeta::new(contributor_ata, false),
-        AccountMeta::new(vault, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
+) -> InstructionResult {
+    let maker = get_maker(&fixture.fundraiser_account);
+    execute_contribute_with_data(
+        mollusk,
+        fixture,
+        contributor,
+        Contribute::new(contributor.wallet, maker, fixture.mint_to_raise, amount)
+    )
+}
 
-    // Create instruction data
-    let ix_data = ContributeIxData {
-        amount,
-        contributor_bump,
-        fundraiser_bump,
-    };
+/// Maker of an initialized fundraiser account, which the builders derive it from
+pub fn get_maker(fundraiser_account: &Account) -> Pubkey {
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(&fundraiser_account.data).unwrap() };
+    Pubkey::new_from_array(fundraiser_state.maker)
+}
 
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![1]; // Ix discriminator = 1
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(&ix_data) });
 
-    // Create instruction
-    let instruction = Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts);
+/// Execute the given contribute instruction
+pub fn execute_contribute_with_data(
+    mollusk: &Mollusk,
+    fixture: &FundraiserFixture,
+    contributor: &ContributorFixture,
+    contribute: Contribute
+) -> InstructionResult {
+    execute_contribute_with_accounts(mollusk, fixture, contributor, contribute, vec![])
+}
 
-    // Create transaction accounts
-    let tx_accounts = &vec![
-        (contributor, contributor_account),
-        (mint_to_raise, mint_to_raise_account),
-        (fundraiser, fundraiser_account),
-        (contributor_acc, contributor_acc_account),
-        (contributor_ata, contributor_ata_account),
-        (vault, vault_account),
-        (system_program, system_account),
-        (token_program, token_account)
-    ];
+/// Execute the given contribute instruction with the accounts of its
+/// optional extras (round, beneficiary, fee payer)
+pub fn execute_contribute_with_accounts(
+    mollusk: &Mollusk,
+    fixture: &FundraiserFixture,
+    contributor: &ContributorFixture,
+    contribute: Contribute,
+    extra_accounts: Vec<(Pubkey, Account)>
+) -> InstructionResult {
+    let mut tx_accounts = get_contribute_accounts(mollusk, fixture, contributor, &contribute);
+    tx_accounts.extend(extra_accounts);
 
     // Process instruction
-    let result = mollusk.process_and_validate_instruction(
-        &instruction,
-        tx_accounts,
-        &[Check::success()]
+    let instruction = contribute.instruction();
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()])
+}
+
+/// Accounts of a contribute instruction, without its optional extras
+pub fn get_contribute_accounts(
+    mollusk: &Mollusk,
+    fixture: &FundraiserFixture,
+    contributor: &ContributorFixture,
+    contribute: &Contribute
+) -> Vec<(Pubkey, Account)> {
+    // Receipt accounts as left by initialize
+    let (receipt_mint, _) = find_receipt_mint_address(&fixture.fundraiser);
+    let receipt_mint_account = get_receipt_mint_account(mollusk, fixture.fundraiser, 0);
+    let beneficiary = contribute.beneficiary.unwrap_or(contributor.wallet);
+    let (receipt_ata, receipt_ata_account) = get_receipt_ata(
+        mollusk,
+        beneficiary,
+        fixture.fundraiser,
+        0
     );
 
-    result
+    vec![
+        (contributor.wallet, contributor.wallet_account.clone()),
+        (fixture.mint_to_raise, fixture.mint_to_raise_account.clone()),
+        (fixture.fundraiser, fixture.fundraiser_account.clone()),
+        (contributor.contributor_acc, contributor.contributor_acc_account.clone()),
+        (contributor.ata, contributor.ata_account.clone()),
+        (fixture.vault, fixture.vault_account.clone()),
+        (fixture.system_program, fixture.system_account.clone()),
+        (fixture.token_program, fixture.token_account.clone()),
+        (receipt_mint, receipt_mint_account),
+        (receipt_ata, receipt_ata_account)
+    ]
 }
 
-pub fn execute_checker(
-    mollusk: &Mollusk,
-    maker: Pubkey,
-    maker_account: Account,
-    mint_to_raise: Pubkey,
-    mint_to_raise_account: Account,
-    fundraiser: Pubkey,
-    fundraiser_account: Account,
-    vault: Pubkey,
-    vault_account: Account,
-    system_program: Pubkey,
-    system_account: Account,
-    token_program: Pubkey,
-    token_account: Account
-) -> mollusk_svm::result::InstructionResult {
+pub fn execute_checker(mollusk: &Mollusk, fixture: &FundraiserFixture) -> InstructionResult {
     // Create maker ATA
-    let maker_ata = Pubkey::new_unique();
+    let maker_ata = get_associated_token_address(&fixture.maker, &fixture.mint_to_raise);
     let mut maker_ata_account = Account::new(
         mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
         spl_token::state::Account::LEN,
-        &token_program
+        &fixture.token_program
     );
     solana_sdk::program_pack::Pack
         ::pack(
             spl_token::state::Account {
                 amount: 0,
-                mint: mint_to_raise,
-                owner: maker,
+                mint: fixture.mint_to_raise,
+                owner: fixture.maker,
                 delegate: COption::None,
                 state: spl_token::state::AccountState::Initialized,
                 close_authority: COption::None,
@@ -396,362 +571,2808 @@ pub fn execute_checker(
         )
         .unwrap();
 
-    // Create instruction accounts
-    let ix_accounts = vec![
-        AccountMeta::new(maker, true),
-        AccountMeta::new(mint_to_raise, false),
-        AccountMeta::new(fundraiser, true),
-        AccountMeta::new(vault, false),
-        AccountMeta::new(maker_ata, false),
-        AccountMeta::new_readonly(system_program, false),
-        AccountMeta::new_readonly(token_program, false)
-    ];
-
-    // Create instruction data
-    let ix_data = InitializeIxData {
-        amount: 0,
-        duration: 0,
-        bump: 0,
-    };
-
-    // Serialize instruction with discriminator
-    let mut ser_ix_data = vec![2]; // Ix discriminator = 2
-    ser_ix_data.extend_from_slice(unsafe { to_bytes(&ix_data) });
-
-    // Create instruction
-    let instruction = Instruction::new_with_bytes(PROGRAM, &ser_ix_data, ix_accounts);
+    let instruction = (CheckContribution {
+        maker: fixture.maker,
+        mint_to_raise: fixture.mint_to_raise,
+        uses_registry: false,
+    }).instruction();
 
     // Create transaction accounts
-    let tx_accounts = &vec![
-        (maker, maker_account),
-        (mint_to_raise, mint_to_raise_account),
-        (fundraiser, fundraiser_account),
-        (vault, vault_account),
+    let tx_accounts = [
+        (fixture.maker, fixture.maker_account.clone()),
+        (fixture.mint_to_raise, fixture.mint_to_raise_account.clone()),
+        (fixture.fundraiser, fixture.fundraiser_account.clone()),
+        (fixture.vault, fixture.vault_account.clone()),
         (maker_ata, maker_ata_account),
-        (system_program, system_account),
-        (token_program, token_account)
+        (fixture.system_program, fixture.system_account.clone()),
+        (fixture.token_program, fixture.token_account.clone()),
     ];
 
     // Process instruction
-    let result = mollusk.process_and_validate_instruction(
-        &instruction,
-        tx_accounts,
-        &[Check::success()]
-    );
-
-    result
+    mollusk.process_and_validate_instruction(&instruction, &tx_accounts, &[Check::success()])
 }
 
 #[test]
 fn test_initialize_fundraiser() {
-    let mollusk = mollusk();
+    let mut mollusk = mollusk();
     // get states
-    let (
-        maker,
-        _contributor,
-        fundraiser,
-        fundraiser_bump,
-        mint_to_raise,
-        vault,
-        system_program,
-        token_program,
-        mut maker_account,
-         _contributor_account,
-        mut fundraiser_account,
-        mut mint_to_raise_account,
-        mut vault_account,
-        system_account,
-        token_account,
-    ) = setup_fundraiser(&mollusk);
+    let mut fixture = setup_fundraiser(&mollusk);
     // execute fundraiser instruction
     let init_result = execute_initialize(
         &mollusk,
-        maker.clone(),
-        maker_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        vault.clone(),
-        vault_account.clone(),
-        fundraiser_bump.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone(),
+        &fixture,
         100_000_000, // amount to raise is 100 tokens
         1 // duration 1 day
     );
-    maker_account.refresh(&maker, init_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, init_result.clone());
-    vault_account.refresh(&vault, init_result.clone());
-    fundraiser_account.refresh(&fundraiser, init_result.clone());
+    fixture.refresh(&init_result);
     // check result
     assert_eq!(init_result.program_result, ProgramResult::Success);
+
+    // Indexers pick the campaign up from the logs
+    let created = FundraiserCreated {
+        fundraiser: fixture.fundraiser.to_bytes(),
+        maker: fixture.maker.to_bytes(),
+        mint_to_raise: fixture.mint_to_raise.to_bytes(),
+        amount_to_raise: 100_000_000,
+        time_started: mollusk.sysvars.clock.unix_timestamp,
+        duration: 1,
+    };
+    assert_eq!(take_events(&mut mollusk), vec![FundraiserEvent::FundraiserCreated(created)]);
 }
 
 #[test]
 fn test_contribute() {
-    let mollusk = mollusk();
+    let mut mollusk = mollusk();
     // get states
-    let (
-        maker,
-        contributor,
-        fundraiser,
-        fundraiser_bump,
-        mint_to_raise,
-        vault,
-        system_program,
-        token_program,
-        mut maker_account,
-         contributor_account,
-        mut fundraiser_account,
-        mut mint_to_raise_account,
-        mut vault_account,
-        system_account,
-        token_account,
-    ) = setup_fundraiser(&mollusk);
+    let mut fixture = setup_fundraiser(&mollusk);
     // execute fundraiser instruction
     let init_result = execute_initialize(
         &mollusk,
-        maker.clone(),
-        maker_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        vault.clone(),
-        vault_account.clone(),
-        fundraiser_bump.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone(),
+        &fixture,
         10_000_000, // amount to raise is 10 tokens
         1 // duration 1 day
     );
-    maker_account.refresh(&maker, init_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, init_result.clone());
-    vault_account.refresh(&vault, init_result.clone());
-    fundraiser_account.refresh(&fundraiser, init_result.clone());
+    fixture.refresh(&init_result);
     // check result
     assert_eq!(init_result.program_result, ProgramResult::Success);
 
     // setup contributor
-    let (
-        contributor_acc,
-        contributor_bump,
-        contributor_ata,
-        mut contributor_acc_account,
-        mut contributor_ata_account,
-    ) = setup_contributor(
-        &mollusk,
-        contributor.clone(),
-        fundraiser.clone(),
-        mint_to_raise.clone(),
-        system_program.clone(),
-        token_program.clone()
-    );
+    let contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
 
     // execute contribute instruction
     let contribute_result = execute_contribute(
         &mollusk,
-        contributor.clone(),
-        contributor_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        contributor_acc.clone(),
-        contributor_acc_account.clone(),
-        contributor_ata.clone(),
-        contributor_ata_account.clone(),
-        vault.clone(),
-        vault_account.clone().clone(),
-        contributor_bump.clone(),
-        fundraiser_bump.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone(),
-
+        &fixture,
+        &contributor,
         1_000_000 // amount to contribute is 1 token
     );
-    contributor_acc_account.refresh(&contributor, contribute_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, contribute_result.clone());
-    vault_account.refresh(&vault, contribute_result.clone());
-    contributor_ata_account.refresh(&contributor_ata, contribute_result.clone());
-    fundraiser_account.refresh(&fundraiser, contribute_result.clone());
-
     assert_eq!(contribute_result.program_result, ProgramResult::Success);
+    let contributed = Contributed {
+        fundraiser: fixture.fundraiser.to_bytes(),
+        contributor: contributor.wallet.to_bytes(),
+        amount: 1_000_000,
+        current_amount: 1_000_000,
+    };
+    let events = take_events(&mut mollusk);
+    assert_eq!(events.last(), Some(&FundraiserEvent::Contributed(contributed)));
+
+    // Receipts are minted 1:1
+    let (receipt_ata, _) = get_receipt_ata(&mollusk, contributor.wallet, fixture.fundraiser, 0);
+    let receipt_ata_account = contribute_result.get_account(&receipt_ata).unwrap();
+    let receipt_ata_state = spl_token::state::Account::unpack(&receipt_ata_account.data).unwrap();
+    assert_eq!(receipt_ata_state.amount, 1_000_000);
 }
 
 #[test]
 fn test_checker() {
     let mollusk = mollusk();
     // get states
-    let (
-        maker,
-        contributor,
-        fundraiser,
-        fundraiser_bump,
-        mint_to_raise,
-        vault,
-        system_program,
-        token_program,
-        mut maker_account,
-        mut contributor_account,
-        mut fundraiser_account,
-        mut mint_to_raise_account,
-        mut vault_account,
-        system_account,
-        token_account,
-    ) = setup_fundraiser(&mollusk);
+    let mut fixture = setup_fundraiser(&mollusk);
     // execute fundraiser instruction
     let init_result = execute_initialize(
         &mollusk,
-        maker.clone(),
-        maker_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        vault.clone(),
-        vault_account.clone(),
-        fundraiser_bump.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone(),
+        &fixture,
         10_000_000, // amount to raise is 10 tokens
         1 // duration 1 day
     );
-    maker_account.refresh(&maker, init_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, init_result.clone());
-    vault_account.refresh(&vault, init_result.clone());
-    fundraiser_account.refresh(&fundraiser, init_result.clone());
+    fixture.refresh(&init_result);
     // check result
     assert_eq!(init_result.program_result, ProgramResult::Success);
 
     // setup the original contributor
-    let (
-        contributor_acc,
-        contributor_bump,
-        contributor_ata,
-        mut contributor_acc_account,
-        mut contributor_ata_account,
-    ) = setup_contributor(
-        &mollusk,
-        contributor.clone(),
-        fundraiser.clone(),
-        mint_to_raise.clone(),
-        system_program.clone(),
-        token_program.clone()
-    );
+    let mut contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
 
     // execute contribute instruction
     let contribute_result = execute_contribute(
         &mollusk,
-        contributor.clone(),
-        contributor_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        contributor_acc.clone(),
-        contributor_acc_account.clone(),
-        contributor_ata.clone(),
-        contributor_ata_account.clone(),
-        vault.clone(),
-        vault_account.clone().clone(),
-        contributor_bump.clone(),
-        fundraiser_bump.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone(),
-
+        &fixture,
+        &contributor,
         1_000_000 // amount to contribute is 1 token
     );
-    contributor_account.refresh(&contributor, contribute_result.clone());
-    contributor_acc_account.refresh(&contributor_acc, contribute_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, contribute_result.clone());
-    vault_account.refresh(&vault, contribute_result.clone());
-    contributor_ata_account.refresh(&contributor_ata, contribute_result.clone());
-    fundraiser_account.refresh(&fundraiser, contribute_result.clone());
+    contributor.refresh(&contribute_result);
+    fixture.refresh(&contribute_result);
     assert_eq!(contribute_result.program_result, ProgramResult::Success);
 
     // lets do this 9 more times
     for _ in 0..9 {
-        let (new_contributor, mut new_contributor_account) = get_new_contributor_account(
-            &mollusk,
-            system_program.clone()
-        );
-        let (
-            new_contributor_acc,
-            new_contributor_bump,
-            new_contributor_ata,
-            mut new_contributor_acc_account,
-            mut new_contributor_ata_account,
-        ) = setup_contributor(
-            &mollusk,
-            new_contributor.clone(),
-            fundraiser.clone(),
-            mint_to_raise.clone(),
-            system_program.clone(),
-            token_program.clone()
-        );
+        let new_contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
         let contribute_result = execute_contribute(
             &mollusk,
-            new_contributor.clone(),
-            new_contributor_account.clone(),
-            mint_to_raise.clone(),
-            mint_to_raise_account.clone(),
-            fundraiser.clone(),
-            fundraiser_account.clone(),
-            new_contributor_acc.clone(),
-            new_contributor_acc_account.clone(),
-            new_contributor_ata.clone(),
-            new_contributor_ata_account.clone(),
-            vault.clone(),
-            vault_account.clone().clone(),
-            new_contributor_bump.clone(),
-            fundraiser_bump.clone(),
-            system_program.clone(),
-            system_account.clone(),
-            token_program.clone(),
-            token_account.clone(),
-
+            &fixture,
+            &new_contributor,
             1_000_000 // amount to contribute is 1 token
         );
-        new_contributor_account.refresh(&new_contributor, contribute_result.clone());
-        new_contributor_acc_account.refresh(&new_contributor_acc, contribute_result.clone());
-        mint_to_raise_account.refresh(&mint_to_raise, contribute_result.clone());
-        vault_account.refresh(&vault, contribute_result.clone());
-        new_contributor_ata_account.refresh(&new_contributor_ata, contribute_result.clone());
-        fundraiser_account.refresh(&fundraiser, contribute_result.clone());
+        fixture.refresh(&contribute_result);
         assert_eq!(contribute_result.program_result, ProgramResult::Success);
     }
 
     // lets now check the fundraiser account
-    let checker_result = execute_checker(
-        &mollusk,
-        maker.clone(),
-        maker_account.clone(),
-        mint_to_raise.clone(),
-        mint_to_raise_account.clone(),
-        fundraiser.clone(),
-        fundraiser_account.clone(),
-        vault.clone(),
-        vault_account.clone(),
-        system_program.clone(),
-        system_account.clone(),
-        token_program.clone(),
-        token_account.clone()
-    );
-    maker_account.refresh(&maker, checker_result.clone());
-    mint_to_raise_account.refresh(&mint_to_raise, checker_result.clone());
-    vault_account.refresh(&vault, checker_result.clone());
-    fundraiser_account.refresh(&fundraiser, checker_result.clone());
+    let checker_result = execute_checker(&mollusk, &fixture);
+    fixture.refresh(&checker_result);
     assert_eq!(checker_result.program_result, ProgramResult::Success);
+
+    // The fundraiser stays open until every contributor account is closed
+    let fundraiser_state = unsafe {
+        load_acc::<Fundraiser>(&fixture.fundraiser_account.data).unwrap()
+    };
+    assert!(fundraiser_state.settled.get());
+    assert_eq!(fundraiser_state.contributor_count.get(), 10);
+
+    let contributor_lamports = contributor.wallet_account.lamports;
+    let instruction = (CloseContributor {
+        contributor: contributor.wallet,
+        maker: fixture.maker,
+        rent_payer: None,
+    }).instruction();
+    let close_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (contributor.wallet, contributor.wallet_account.clone()),
+            (fixture.maker, fixture.maker_account.clone()),
+            (fixture.fundraiser, fixture.fundraiser_account.clone()),
+            (contributor.contributor_acc, contributor.contributor_acc_account.clone()),
+        ],
+        &[Check::success()]
+    );
+    assert_eq!(
+        close_result.get_account(&contributor.wallet).unwrap().lamports,
+        contributor_lamports + contributor.contributor_acc_account.lamports
+    );
+    let fundraiser_data = &close_result.get_account(&fixture.fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.contributor_count.get(), 9);
+}
+
+#[test]
+fn test_close_contributor_of_other_fundraiser() {
+    let mollusk = mollusk();
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let contributor = Pubkey::new_unique();
+    let contributor_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+
+    // A settled fundraiser with one open contributor account
+    let maker = Pubkey::new_unique();
+    let (fundraiser, bump) = find_fundraiser_address(&maker);
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.initialize(maker.to_bytes(), [2; 32], 10_000_000, 5, bump, 0);
+    fundraiser_state.settled.set(true);
+    fundraiser_state.contributor_count.set(1);
+    let mut fundraiser_account = Account::new(LAMPORTS_PER_SOL, Fundraiser::LEN, &PROGRAM);
+    fundraiser_account.data = unsafe { to_bytes(&fundraiser_state) }.to_vec();
+
+    // The contributor only backed a second, unrelated fundraiser
+    let (other_fundraiser, _) = find_fundraiser_address(&Pubkey::new_unique());
+    let (other_contributor_acc, _) = find_contributor_address(&other_fundraiser, &contributor);
+    let mut contributor_state = Contributor::default();
+    contributor_state.initialize(contributor.to_bytes(), contributor.to_bytes());
+    contributor_state.amount.set(1_000_000);
+    let mut contributor_acc_account = Account::new(LAMPORTS_PER_SOL, Contributor::LEN, &PROGRAM);
+    contributor_acc_account.data = unsafe { to_bytes(&contributor_state) }.to_vec();
+
+    let mut instruction = (CloseContributor { contributor, maker, rent_payer: None }).instruction();
+    instruction.accounts[3].pubkey = other_contributor_acc;
+    let result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (contributor, contributor_account),
+            (maker, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (fundraiser, fundraiser_account),
+            (other_contributor_acc, contributor_acc_account)
+        ]
+    );
+    assert_eq!(
+        result.program_result,
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::InvalidSeeds)
+    );
+}
+
+/// Fundraiser of 10 tokens backed by a single 1 token contribution in its
+/// only tier, with the clock moved past its deadline. The fee payer, if any,
+/// funds the contributor account.
+pub fn setup_failed_fundraiser(
+    mollusk: &mut Mollusk,
+    fee_payer: Option<(Pubkey, Account)>
+) -> (Pubkey, Pubkey, Pubkey, InstructionResult) {
+    let mut fixture = setup_fundraiser(mollusk);
+    let mut tiers = [TierIxData::default(); MAX_TIERS];
+    tiers[0] = TierIxData {
+        min_amount: 1_000_000,
+        max_backers: 10,
+        label_id: 1,
+    };
+    let init_result = execute_initialize_with_data(mollusk, &fixture, InitializeIxData {
+        tier_count: 1,
+        tiers,
+        ..get_initialize_ix_data(fixture.fundraiser, fixture.fundraiser_bump, 10_000_000, 1)
+    });
+    fixture.refresh(&init_result);
+
+    let contributor = setup_contributor(mollusk, &fixture, Pubkey::new_unique());
+    let contribute_result = execute_contribute_with_accounts(
+        mollusk,
+        &fixture,
+        &contributor,
+        Contribute {
+            fee_payer: fee_payer.as_ref().map(|(key, _)| *key),
+            tier: 0,
+            ..Contribute::new(contributor.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+        },
+        fee_payer.into_iter().collect()
+    );
+
+    mollusk.sysvars.clock.unix_timestamp += 2 * SECONDS_TO_DAYS;
+    (fixture.maker, contributor.wallet, fixture.mint_to_raise, contribute_result)
+}
+
+/// Execute the given refund instruction against the accounts left by the
+/// contribution
+pub fn execute_refund(
+    mollusk: &Mollusk,
+    contribute_result: &InstructionResult,
+    refund: Refund
+) -> InstructionResult {
+    let instruction = refund.instruction();
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let tx_accounts: Vec<_> = instruction.accounts
+        .iter()
+        .map(|meta| {
+            // The maker is the only account the contribution did not touch
+            let account = if meta.pubkey == refund.maker {
+                Account::new(LAMPORTS_PER_SOL, 0, &system_program)
+            } else {
+                contribute_result.get_account(&meta.pubkey).unwrap().clone()
+            };
+            (meta.pubkey, account)
+        })
+        .collect();
+    mollusk.process_instruction(&instruction, &tx_accounts)
+}
+
+#[test]
+fn test_refund() {
+    let mut mollusk = mollusk();
+    let (maker, contributor, mint_to_raise, contribute_result) = setup_failed_fundraiser(
+        &mut mollusk,
+        None
+    );
+    let (fundraiser, _) = find_fundraiser_address(&maker);
+    let (contributor_acc, _) = find_contributor_address(&fundraiser, &contributor);
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let receipt_ata = get_associated_token_address(&contributor, &receipt_mint);
+    let contributor_ata = get_associated_token_address(&contributor, &mint_to_raise);
+    let contributor_lamports = contribute_result.get_account(&contributor).unwrap().lamports;
+    let rent = contribute_result.get_account(&contributor_acc).unwrap().lamports;
+
+    let refund_result = execute_refund(&mollusk, &contribute_result, Refund {
+        contributor,
+        maker,
+        mint_to_raise,
+        rent_payer: None,
+    });
+    assert_eq!(refund_result.program_result, ProgramResult::Success);
+    let refunded = Refunded {
+        fundraiser: fundraiser.to_bytes(),
+        contributor: contributor.to_bytes(),
+        amount: 1_000_000,
+    };
+    assert_eq!(take_events(&mut mollusk).last(), Some(&FundraiserEvent::Refunded(refunded)));
+
+    // The receipts are burned and the contribution returned
+    let receipt_ata_account = refund_result.get_account(&receipt_ata).unwrap();
+    let receipt_ata_state = spl_token::state::Account::unpack(&receipt_ata_account.data).unwrap();
+    assert_eq!(receipt_ata_state.amount, 0);
+    let receipt_mint_account = refund_result.get_account(&receipt_mint).unwrap();
+    let receipt_mint_state = spl_token::state::Mint::unpack(&receipt_mint_account.data).unwrap();
+    assert_eq!(receipt_mint_state.supply, 0);
+    let contributor_ata_account = refund_result.get_account(&contributor_ata).unwrap();
+    let contributor_ata_state = spl_token::state::Account::unpack(
+        &contributor_ata_account.data
+    ).unwrap();
+    assert_eq!(contributor_ata_state.amount, 1_000_000);
+
+    // The contributor account is closed into the contributor who funded it
+    assert_eq!(refund_result.get_account(&contributor_acc).unwrap().lamports, 0);
+    assert_eq!(
+        refund_result.get_account(&contributor).unwrap().lamports,
+        contributor_lamports + rent
+    );
+    // The contribution leaves the totals, the same as a registry refund
+    let fundraiser_data = &refund_result.get_account(&fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.contributor_count.get(), 0);
+    assert_eq!(fundraiser_state.current_amount.get(), 0);
+    assert_eq!(fundraiser_state.total_weight.get(), 0);
+    assert_eq!(fundraiser_state.tiers[0].backers.get(), 0);
+
+    // Refunding everything does not unlock the metadata
+    assert!(fundraiser_state.metadata_locked.get());
+    let (metadata, metadata_bump) = find_metadata_address(&fundraiser);
+    let instruction = (UpdateMetadata {
+        maker,
+        metadata: get_metadata_ix_data("Renamed campaign", metadata_bump),
+    }).instruction();
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let update_result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (maker, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (fundraiser, refund_result.get_account(&fundraiser).unwrap().clone()),
+            (metadata, Account::new(LAMPORTS_PER_SOL, FundraiserMetadata::LEN, &PROGRAM))
+        ]
+    );
+    assert_eq!(
+        update_result.program_result,
+        ProgramResult::Failure(
+            solana_sdk::program_error::ProgramError::Custom(FundraiserError::MetadataLocked as u32)
+        )
+    );
+}
+
+#[test]
+fn test_refund_to_fee_payer() {
+    let mut mollusk = mollusk();
+    let (system_program, _) = program::keyed_account_for_system_program();
+    let fee_payer = Pubkey::new_unique();
+    let fee_payer_account = Account::new(LAMPORTS_PER_SOL, 0, &system_program);
+    let (maker, contributor, mint_to_raise, contribute_result) = setup_failed_fundraiser(
+        &mut mollusk,
+        Some((fee_payer, fee_payer_account))
+    );
+    let contributor_lamports = contribute_result.get_account(&contributor).unwrap().lamports;
+
+    // The recorded fee payer must be passed to get its rent back
+    let refund_result = execute_refund(&mollusk, &contribute_result, Refund {
+        contributor,
+        maker,
+        mint_to_raise,
+        rent_payer: None,
+    });
+    assert_eq!(
+        refund_result.program_result,
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::NotEnoughAccountKeys)
+    );
+
+    let refund_result = execute_refund(&mollusk, &contribute_result, Refund {
+        contributor,
+        maker,
+        mint_to_raise,
+        rent_payer: Some(fee_payer),
+    });
+    assert_eq!(refund_result.program_result, ProgramResult::Success);
+    assert_eq!(refund_result.get_account(&fee_payer).unwrap().lamports, LAMPORTS_PER_SOL);
+    assert_eq!(refund_result.get_account(&contributor).unwrap().lamports, contributor_lamports);
+}
+
+/// Build metadata instruction data for tests
+pub fn get_metadata_ix_data(name: &str, bump: u8) -> MetadataIxData {
+    let mut name_bytes = [0u8; MAX_NAME_LEN];
+    name_bytes[..name.len()].copy_from_slice(name.as_bytes());
+    let mut symbol = [0u8; MAX_SYMBOL_LEN];
+    symbol[..4].copy_from_slice(b"TEST");
+    let mut uri = [0u8; MAX_URI_LEN];
+    uri[..19].copy_from_slice(b"https://example.com");
+    MetadataIxData {
+        name: name_bytes,
+        symbol,
+        uri,
+        content_hash: [0x07; 32],
+        category: 1,
+        bump,
+    }
+}
+
+#[test]
+fn test_initialize_validation() {
+    let mollusk = mollusk();
+    let FundraiserFixture {
+        maker,
+        fundraiser,
+        fundraiser_bump,
+        mint_to_raise,
+        vault,
+        system_program,
+        token_program,
+        maker_account,
+        fundraiser_account,
+        mint_to_raise_account,
+        vault_account,
+        system_account,
+        token_account,
+    } = setup_fundraiser(&mollusk);
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let initialize = |mint_account: &Account, vault_account: &Account, amount: u64, duration: u8| {
+        let instruction = (Initialize {
+            maker,
+            mint_to_raise,
+            params: get_initialize_ix_data(fundraiser, fundraiser_bump, amount, duration),
+            metadata: None,
+        }).instruction();
+        // Failures are expected, so the result is not validated
+        mollusk.process_instruction(
+            &instruction,
+            &[
+                (maker, maker_account.clone()),
+                (mint_to_raise, mint_account.clone()),
+                (fundraiser, fundraiser_account.clone()),
+                (vault, vault_account.clone()),
+                (receipt_mint, Account::new(0, 0, &system_program)),
+                (system_program, system_account.clone()),
+                (token_program, token_account.clone())
+            ]
+        ).program_result
+    };
+    let failure = |error: FundraiserError| {
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::Custom(error as u32))
+    };
+
+    // The target is `MIN_AMOUNT_TO_RAISE` whole tokens, scaled by the mint decimals
+    assert_eq!(
+        initialize(&mint_to_raise_account, &vault_account, 2_999_999, 1),
+        failure(FundraiserError::InvalidAmount)
+    );
+    assert_eq!(
+        initialize(&mint_to_raise_account, &vault_account, 3_000_000, 1),
+        ProgramResult::Success
+    );
+
+    assert_eq!(
+        initialize(&mint_to_raise_account, &vault_account, 100_000_000, 0),
+        failure(FundraiserError::InvalidDuration)
+    );
+    assert_eq!(
+        initialize(&mint_to_raise_account, &vault_account, 100_000_000, MAX_DURATION_DAYS + 1),
+        failure(FundraiserError::InvalidDuration)
+    );
+
+    let mut foreign_mint_account = mint_to_raise_account.clone();
+    foreign_mint_account.owner = system_program;
+    assert_eq!(
+        initialize(&foreign_mint_account, &vault_account, 100_000_000, 1),
+        failure(FundraiserError::InvalidMintOwner)
+    );
+
+    let mut uninitialized_mint_account = mint_to_raise_account.clone();
+    uninitialized_mint_account.data = vec![0; spl_token::state::Mint::LEN];
+    assert_eq!(
+        initialize(&uninitialized_mint_account, &vault_account, 100_000_000, 1),
+        failure(FundraiserError::MintNotInitialized)
+    );
+
+    let mut other_vault_account = vault_account.clone();
+    let mut vault_state = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+    vault_state.mint = Pubkey::new_unique();
+    spl_token::state::Account::pack(vault_state, &mut other_vault_account.data).unwrap();
+    assert_eq!(
+        initialize(&mint_to_raise_account, &other_vault_account, 100_000_000, 1),
+        failure(FundraiserError::InvalidVaultMint)
+    );
+
+    let mut foreign_vault_account = vault_account.clone();
+    let mut vault_state = spl_token::state::Account::unpack(&vault_account.data).unwrap();
+    vault_state.owner = maker;
+    spl_token::state::Account::pack(vault_state, &mut foreign_vault_account.data).unwrap();
+    assert_eq!(
+        initialize(&mint_to_raise_account, &foreign_vault_account, 100_000_000, 1),
+        failure(FundraiserError::InvalidVaultOwner)
+    );
+}
+
+#[test]
+fn test_initialize_with_metadata() {
+    let mollusk = mollusk();
+    let FundraiserFixture {
+        maker,
+        fundraiser,
+        fundraiser_bump,
+        mint_to_raise,
+        vault,
+        system_program,
+        token_program,
+        maker_account,
+        fundraiser_account,
+        mint_to_raise_account,
+        vault_account,
+        system_account,
+        token_account,
+    } = setup_fundraiser(&mollusk);
+
+    // Derive metadata PDA
+    let (metadata, metadata_bump) = find_metadata_address(&fundraiser);
+    let metadata_account = Account::new(0, 0, &system_program);
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+
+    // Metadata is appended to the initialize data
+    let instruction = (Initialize {
+        maker,
+        mint_to_raise,
+        params: get_initialize_ix_data(fundraiser, fundraiser_bump, 10_000_000, 1),
+        metadata: Some(get_metadata_ix_data("My campaign", metadata_bump)),
+    }).instruction();
+
+    let tx_accounts = &[
+        (maker, maker_account),
+        (mint_to_raise, mint_to_raise_account),
+        (fundraiser, fundraiser_account),
+        (vault, vault_account),
+        (receipt_mint, Account::new(0, 0, &system_program)),
+        (system_program, system_account),
+        (token_program, token_account),
+        (metadata, metadata_account)
+    ];
+    let init_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        tx_accounts,
+        &[Check::success(), Check::account(&metadata).owner(&PROGRAM).build()]
+    );
+    assert_eq!(init_result.program_result, ProgramResult::Success);
+
+    // Update metadata before any contribution
+    let update_ix_data = get_metadata_ix_data("Renamed campaign", metadata_bump);
+    let instruction = (UpdateMetadata { maker, metadata: update_ix_data.clone() }).instruction();
+    let tx_accounts = &[
+        (maker, init_result.get_account(&maker).unwrap().clone()),
+        (fundraiser, init_result.get_account(&fundraiser).unwrap().clone()),
+        (metadata, init_result.get_account(&metadata).unwrap().clone())
+    ];
+    let update_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        tx_accounts,
+        &[Check::success()]
+    );
+    let metadata_data = &update_result.get_account(&metadata).unwrap().data;
+    let metadata_state = unsafe {
+        load_acc::<FundraiserMetadata>(metadata_data).unwrap()
+    };
+    assert_eq!(metadata_state.fundraiser, fundraiser.to_bytes());
+    assert_eq!(metadata_state.name, update_ix_data.name);
+}
+
+#[test]
+fn test_contribute_with_tier() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+
+    // One tier of at least 1 token, capped at a single backer
+    let mut tiers = [TierIxData::default(); MAX_TIERS];
+    tiers[0] = TierIxData {
+        min_amount: 1_000_000,
+        max_backers: 1,
+        label_id: 7,
+    };
+    let init_result = execute_initialize_with_data(&mollusk, &fixture, InitializeIxData {
+        tier_count: 1,
+        tiers,
+        ..get_initialize_ix_data(fixture.fundraiser, fixture.fundraiser_bump, 10_000_000, 1)
+    });
+    fixture.refresh(&init_result);
+
+    let contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contribute_result = execute_contribute_with_data(
+        &mollusk,
+        &fixture,
+        &contributor,
+        Contribute {
+            tier: 0,
+            ..Contribute::new(contributor.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+        }
+    );
+    assert_eq!(contribute_result.program_result, ProgramResult::Success);
+
+    let contributor_data = &contribute_result
+        .get_account(&contributor.contributor_acc)
+        .unwrap().data;
+    let contributor_state = unsafe { load_acc::<Contributor>(contributor_data).unwrap() };
+    assert_eq!(contributor_state.tier, 0);
+    assert_eq!(contributor_state.amount.get(), 1_000_000);
+
+    let fundraiser_data = &contribute_result.get_account(&fixture.fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.tiers[0].backers.get(), 1);
+    assert!(fundraiser_state.tiers[0].is_full());
+}
+
+#[test]
+fn test_merkle_proof() {
+    let keys: Vec<[u8; 32]> = (0..3).map(|_| Pubkey::new_unique().to_bytes()).collect();
+    let leaves: Vec<[u8; 32]> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| merkle::leaf(key, i as u64))
+        .collect();
+
+    // Three leaves, the last one is promoted to the second level
+    let left = merkle::node(&leaves[0], &leaves[1]);
+    let root = merkle::node(&left, &leaves[2]);
+
+    let mut proof = leaves[1].to_vec();
+    proof.extend_from_slice(&leaves[2]);
+    assert!(merkle::verify(&proof, &root, leaves[0]));
+    assert!(merkle::verify(&left, &root, leaves[2]));
+
+    // Wrong cap, wrong leaf and truncated proofs are rejected
+    assert!(!merkle::verify(&proof, &root, merkle::leaf(&keys[0], 5)));
+    assert!(!merkle::verify(&proof, &root, leaves[1]));
+    assert!(!merkle::verify(&proof[..40], &root, leaves[0]));
+}
+
+#[test]
+fn test_contribute_allowlisted() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let contributor = Pubkey::new_unique();
+
+    // Single entry allowlist capped at 2 tokens, the root is the leaf itself
+    let cap = 2_000_000u64;
+    let merkle_root = merkle::leaf(&contributor.to_bytes(), cap);
+    let init_result = execute_initialize_with_data(&mollusk, &fixture, InitializeIxData {
+        merkle_root,
+        ..get_initialize_ix_data(fixture.fundraiser, fixture.fundraiser_bump, 10_000_000, 1)
+    });
+    fixture.refresh(&init_result);
+
+    let contributor = setup_contributor(&mollusk, &fixture, contributor);
+    let contribute_result = execute_contribute_with_data(
+        &mollusk,
+        &fixture,
+        &contributor,
+        Contribute {
+            allowlist: Some(AllowlistProof { cap, proof: vec![] }),
+            ..Contribute::new(contributor.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+        }
+    );
+    assert_eq!(contribute_result.program_result, ProgramResult::Success);
+}
+
+#[test]
+fn test_bonus_weight() {
+    // +20% in the first day, +10% in the first week
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.bonus_periods[0] = BonusPeriod {
+        duration: SECONDS_TO_DAYS.into(),
+        bonus_bps: 2_000.into(),
+    };
+    fundraiser_state.bonus_periods[1] = BonusPeriod {
+        duration: (7 * SECONDS_TO_DAYS).into(),
+        bonus_bps: 1_000.into(),
+    };
+    assert!(Fundraiser::is_valid_bonus_schedule(&fundraiser_state.bonus_periods));
+
+    assert_eq!(fundraiser_state.weight(1_000_000, 0), 1_200_000);
+    assert_eq!(fundraiser_state.weight(1_000_000, SECONDS_TO_DAYS - 1), 1_200_000);
+    assert_eq!(fundraiser_state.weight(1_000_000, SECONDS_TO_DAYS), 1_100_000);
+    assert_eq!(fundraiser_state.weight(1_000_000, 7 * SECONDS_TO_DAYS), 1_000_000);
+
+    // Periods must be increasing and unused periods must come last
+    fundraiser_state.bonus_periods.swap(0, 1);
+    assert!(!Fundraiser::is_valid_bonus_schedule(&fundraiser_state.bonus_periods));
+    fundraiser_state.bonus_periods.swap(0, 2);
+    assert!(!Fundraiser::is_valid_bonus_schedule(&fundraiser_state.bonus_periods));
+}
+
+#[test]
+fn test_isqrt() {
+    for value in [0u64, 1, 2, 3, 4, 15, 16, 17, 999_999, 1_000_000, u32::MAX as u64, u64::MAX] {
+        let root = isqrt(value) as u128;
+        assert!(root * root <= (value as u128));
+        assert!((root + 1) * (root + 1) > (value as u128));
+    }
+}
+
+#[test]
+fn test_quadratic_match() {
+    let mut round_state = Round::default();
+    let broad = Pubkey::new_unique().to_bytes();
+    let whale = Pubkey::new_unique().to_bytes();
+    let failed = Pubkey::new_unique().to_bytes();
+    round_state.add_entry(broad, [0x01; 32], 4_000_000);
+    round_state.add_entry(whale, [0x02; 32], 4_000_000);
+    round_state.add_entry(failed, [0x03; 32], 4_000_000);
+
+    // Four backers of 1 token vs one backer of 4 tokens
+    for _ in 0..4 {
+        round_state.track_contribution(&broad, 0, 1_000_000).unwrap();
+    }
+    round_state.track_contribution(&whale, 0, 1_000_000).unwrap();
+    round_state.track_contribution(&whale, 1_000_000, 3_000_000).unwrap();
+    round_state.track_contribution(&failed, 0, 1_000_000).unwrap();
+    assert!(round_state.track_contribution(&[0x09; 32], 0, 1).is_none());
+
+    assert_eq!(round_state.entries[0].sum_sqrt.get(), 4_000);
+    assert_eq!(round_state.entries[1].sum_sqrt.get(), 2_000);
+    assert_eq!(round_state.entries[1].current_amount.get(), 4_000_000);
+
+    // (4 * 1000)^2 - 4M = 12M, (2000)^2 - 4M = 0, unsuccessful campaigns get nothing
+    assert_eq!(round_state.entries[0].match_weight(), 12_000_000);
+    assert_eq!(round_state.entries[1].match_weight(), 0);
+    assert_eq!(round_state.entries[2].match_weight(), 0);
+
+    round_state.matching_pool.set(5_000_000);
+    assert_eq!(round_state.match_amount(&round_state.entries[0]), 5_000_000);
+    assert_eq!(round_state.match_amount(&round_state.entries[1]), 0);
+}
+
+#[test]
+fn test_join_round() {
+    let mollusk = mollusk();
+    let fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    let FundraiserFixture {
+        maker,
+        fundraiser,
+        mint_to_raise,
+        system_program,
+        token_program,
+        system_account,
+        ..
+    } = fixture;
+
+    // Round authority with a client-initialized matching vault
+    let authority = Pubkey::new_unique();
+    let authority_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
+    let round_id = 1u64;
+    let (round, _) = find_round_address(&authority, round_id);
+    let round_vault = get_associated_token_address(&round, &mint_to_raise);
+    let mut round_vault_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &token_program
+    );
+    solana_sdk::program_pack::Pack
+        ::pack(
+            spl_token::state::Account {
+                amount: 5_000_000,
+                mint: mint_to_raise,
+                owner: round,
+                delegate: COption::None,
+                state: spl_token::state::AccountState::Initialized,
+                close_authority: COption::None,
+                is_native: COption::None,
+                delegated_amount: 0,
+            },
+            round_vault_account.data_as_mut_slice()
+        )
+        .unwrap();
+
+    let instruction = (InitializeRound {
+        authority,
+        mint: mint_to_raise,
+        id: round_id,
+        end_time: mollusk.sysvars.clock.unix_timestamp + 7 * SECONDS_TO_DAYS,
+    }).instruction();
+    let round_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (authority, authority_account),
+            (round, Account::new(0, 0, &system_program)),
+            (mint_to_raise, init_result.get_account(&mint_to_raise).unwrap().clone()),
+            (round_vault, round_vault_account),
+            (system_program, system_account)
+        ],
+        &[Check::success()]
+    );
+
+    // Maker and round authority both sign to add the campaign
+    let instruction = (JoinRound { maker, authority, round_id }).instruction();
+    let join_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (maker, init_result.get_account(&maker).unwrap().clone()),
+            (fundraiser, init_result.get_account(&fundraiser).unwrap().clone()),
+            (round, round_result.get_account(&round).unwrap().clone()),
+            (authority, round_result.get_account(&authority).unwrap().clone())
+        ],
+        &[Check::success()]
+    );
+
+    let fundraiser_data = &join_result.get_account(&fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.round, round.to_bytes());
+    let round_data = &join_result.get_account(&round).unwrap().data;
+    let round_state = unsafe { load_acc::<Round>(round_data).unwrap() };
+    assert_eq!(round_state.entry_count, 1);
+    assert_eq!(round_state.entries[0].fundraiser, fundraiser.to_bytes());
+}
+
+#[test]
+fn test_distribute_round() {
+    let mut mollusk = mollusk();
+    let FundraiserFixture {
+        maker,
+        mint_to_raise,
+        token_program,
+        mint_to_raise_account,
+        token_account,
+        ..
+    } = setup_fundraiser(&mollusk);
+
+    // A round where only the broadly backed campaign earns a match
+    let authority = Pubkey::new_unique();
+    let (round, round_bump) = find_round_address(&authority, 1);
+    let round_vault = get_associated_token_address(&round, &mint_to_raise);
+    let end_time = mollusk.sysvars.clock.unix_timestamp + SECONDS_TO_DAYS;
+    let mut round_state = Round::default();
+    round_state.initialize(
+        authority.to_bytes(),
+        mint_to_raise.to_bytes(),
+        round_vault.to_bytes(),
+        1,
+        end_time,
+        round_bump
+    );
+    let whale_maker = Pubkey::new_unique();
+    round_state.add_entry([0x01; 32], maker.to_bytes(), 4_000_000);
+    round_state.add_entry([0x02; 32], whale_maker.to_bytes(), 4_000_000);
+    for _ in 0..4 {
+        round_state.track_contribution(&[0x01; 32], 0, 1_000_000).unwrap();
+    }
+    round_state.track_contribution(&[0x02; 32], 0, 4_000_000).unwrap();
+    let mut round_account = Account::new(LAMPORTS_PER_SOL, Round::LEN, &PROGRAM);
+    round_account.data = unsafe { to_bytes(&round_state) }.to_vec();
+
+    let round_vault_account = get_token_account(&mollusk, mint_to_raise, round, 5_000_000);
+    let maker_ata = get_associated_token_address(&maker, &mint_to_raise);
+    let maker_ata_account = get_token_account(&mollusk, mint_to_raise, maker, 0);
+    let whale_maker_ata = get_associated_token_address(&whale_maker, &mint_to_raise);
+    let whale_maker_ata_account = get_token_account(&mollusk, mint_to_raise, whale_maker, 0);
+    let accounts = |round_account: &Account, index: u8| {
+        let (maker, maker_ata, maker_ata_account) = if index == 0 {
+            (maker, maker_ata, &maker_ata_account)
+        } else {
+            (whale_maker, whale_maker_ata, &whale_maker_ata_account)
+        };
+        let instruction = (DistributeRound {
+            authority,
+            round_id: 1,
+            mint: mint_to_raise,
+            maker,
+            index,
+        }).instruction();
+        let tx_accounts = vec![
+            (round, round_account.clone()),
+            (round_vault, round_vault_account.clone()),
+            (mint_to_raise, mint_to_raise_account.clone()),
+            (maker_ata, maker_ata_account.clone()),
+            (token_program, token_account.clone())
+        ];
+        (instruction, tx_accounts)
+    };
+    let failure = |error: FundraiserError| {
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::Custom(error as u32))
+    };
+
+    // Nothing is paid out before the round ends
+    let (instruction, tx_accounts) = accounts(&round_account, 0);
+    assert_eq!(
+        mollusk.process_instruction(&instruction, &tx_accounts).program_result,
+        failure(FundraiserError::RoundNotEnded)
+    );
+
+    mollusk.sysvars.clock.unix_timestamp = end_time;
+    let (instruction, tx_accounts) = accounts(&round_account, 0);
+    let distribute_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts,
+        &[Check::success()]
+    );
+    let maker_ata_account = distribute_result.get_account(&maker_ata).unwrap();
+    let maker_ata_state = spl_token::state::Account::unpack(&maker_ata_account.data).unwrap();
+    assert_eq!(maker_ata_state.amount, 5_000_000);
+    let round_account = distribute_result.get_account(&round).unwrap().clone();
+    let round_state = unsafe { load_acc::<Round>(&round_account.data).unwrap() };
+    assert_eq!(round_state.matching_pool.get(), 5_000_000);
+    assert!(round_state.entries[0].paid.get());
+
+    // Each campaign is paid once, campaigns without weight are not paid
+    let (instruction, tx_accounts) = accounts(&round_account, 0);
+    assert_eq!(
+        mollusk.process_instruction(&instruction, &tx_accounts).program_result,
+        failure(FundraiserError::MatchAlreadyPaid)
+    );
+    let (instruction, tx_accounts) = accounts(&round_account, 1);
+    assert_eq!(
+        mollusk.process_instruction(&instruction, &tx_accounts).program_result,
+        failure(FundraiserError::NotEligibleForMatch)
+    );
+}
+
+#[test]
+fn test_contribute_in_round() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+
+    // The fundraiser joined a running round
+    let authority = Pubkey::new_unique();
+    let (round, round_bump) = find_round_address(&authority, 1);
+    let mut round_state = Round::default();
+    round_state.initialize(
+        authority.to_bytes(),
+        fixture.mint_to_raise.to_bytes(),
+        get_associated_token_address(&round, &fixture.mint_to_raise).to_bytes(),
+        1,
+        mollusk.sysvars.clock.unix_timestamp + SECONDS_TO_DAYS,
+        round_bump
+    );
+    round_state.add_entry(fixture.fundraiser.to_bytes(), fixture.maker.to_bytes(), 10_000_000);
+    let mut round_account = Account::new(LAMPORTS_PER_SOL, Round::LEN, &PROGRAM);
+    round_account.data = unsafe { to_bytes(&round_state) }.to_vec();
+    let fundraiser_state = unsafe {
+        load_acc_mut::<Fundraiser>(&mut fixture.fundraiser_account.data).unwrap()
+    };
+    fundraiser_state.round = round.to_bytes();
+
+    // Gifts are refused, even with the beneficiary's signature
+    let beneficiary = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let payer = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let gift = Contribute {
+        round: Some(round),
+        beneficiary: Some(beneficiary.wallet),
+        ..Contribute::new(payer.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+    };
+    let contributor = ContributorFixture {
+        contributor_acc: beneficiary.contributor_acc,
+        contributor_bump: beneficiary.contributor_bump,
+        contributor_acc_account: beneficiary.contributor_acc_account.clone(),
+        ..payer.clone()
+    };
+    let mut tx_accounts = get_contribute_accounts(&mollusk, &fixture, &contributor, &gift);
+    tx_accounts.push((round, round_account.clone()));
+    tx_accounts.push((beneficiary.wallet, beneficiary.wallet_account.clone()));
+    assert_eq!(
+        mollusk.process_instruction(&gift.instruction(), &tx_accounts).program_result,
+        ProgramResult::Failure(
+            solana_sdk::program_error::ProgramError::Custom(FundraiserError::GiftInRound as u32)
+        )
+    );
+
+    // The payer backing the campaign itself counts towards the match
+    let contribute_result = execute_contribute_with_accounts(
+        &mollusk,
+        &fixture,
+        &payer,
+        Contribute {
+            round: Some(round),
+            ..Contribute::new(payer.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+        },
+        vec![(round, round_account)]
+    );
+    let round_data = &contribute_result.get_account(&round).unwrap().data;
+    let round_state = unsafe { load_acc::<Round>(round_data).unwrap() };
+    assert_eq!(round_state.entries[0].current_amount.get(), 1_000_000);
+}
+
+/// Create a token account of `mint` owned by `owner` holding `amount`
+pub fn get_token_account(mollusk: &Mollusk, mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+    let mut token_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(spl_token::state::Account::LEN),
+        spl_token::state::Account::LEN,
+        &spl_token::ID
+    );
+    solana_sdk::program_pack::Pack
+        ::pack(
+            spl_token::state::Account {
+                amount,
+                mint,
+                owner,
+                delegate: COption::None,
+                state: spl_token::state::AccountState::Initialized,
+                close_authority: COption::None,
+                is_native: COption::None,
+                delegated_amount: 0,
+            },
+            token_account.data_as_mut_slice()
+        )
+        .unwrap();
+    token_account
+}
+
+#[test]
+fn test_pending_match() {
+    let mut pledge_state = MatchPledge::default();
+    pledge_state.initialize([1; 32], [2; 32], [3; 32], 5_000_000, 255);
+    assert_eq!(pledge_state.pending_match(3_000_000), 3_000_000);
+
+    pledge_state.matched.set(3_000_000);
+    assert_eq!(pledge_state.pending_match(0), 0);
+    assert_eq!(pledge_state.pending_match(1_000_000), 1_000_000);
+    // Never more than what is left of the cap
+    assert_eq!(pledge_state.pending_match(10_000_000), 2_000_000);
+
+    // 4 tokens contributed, 3 of them already matched by another pledge
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.initialize([1; 32], [2; 32], 10_000_000, 1, 255, 0);
+    fundraiser_state.current_amount.set(7_000_000);
+    fundraiser_state.matched_amount.set(3_000_000);
+    assert_eq!(fundraiser_state.unmatched_amount(), 1_000_000);
+    // Refunds can leave matches above the remaining contributions
+    fundraiser_state.current_amount.set(5_000_000);
+    assert_eq!(fundraiser_state.unmatched_amount(), 0);
+}
+
+#[test]
+fn test_match_pledge() {
+    let mut mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+    let FundraiserFixture {
+        maker,
+        fundraiser,
+        mint_to_raise,
+        vault,
+        system_program,
+        token_program,
+        ..
+    } = fixture;
+
+    // Sponsor escrows 5 tokens
+    let sponsor = Pubkey::new_unique();
+    let sponsor_account = Account::new(2 * LAMPORTS_PER_SOL, 0, &system_program);
+    let sponsor_ata = get_associated_token_address(&sponsor, &mint_to_raise);
+    let (pledge, _) = find_match_pledge_address(&fundraiser, &sponsor);
+    let pledge_vault = get_associated_token_address(&pledge, &mint_to_raise);
+
+    let instruction = (CreateMatchPledge {
+        sponsor,
+        maker,
+        mint_to_raise,
+        cap: 5_000_000,
+    }).instruction();
+    let pledge_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (sponsor, sponsor_account),
+            (mint_to_raise, fixture.mint_to_raise_account.clone()),
+            (fundraiser, fixture.fundraiser_account.clone()),
+            (pledge, Account::new(0, 0, &system_program)),
+            (pledge_vault, get_token_account(&mollusk, mint_to_raise, pledge, 0)),
+            (sponsor_ata, get_token_account(&mollusk, mint_to_raise, sponsor, 5_000_000)),
+            (system_program, fixture.system_account.clone()),
+            (token_program, fixture.token_account.clone())
+        ],
+        &[Check::success()]
+    );
+
+    // One contribution of 1 token
+    let contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contribute_result = execute_contribute(&mollusk, &fixture, &contributor, 1_000_000);
+    fixture.refresh(&contribute_result);
+
+    // Crank moves the 1:1 match into the vault
+    let instruction = (SettleMatch { sponsor, maker, mint_to_raise }).instruction();
+    let settle_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (mint_to_raise, fixture.mint_to_raise_account.clone()),
+            (fundraiser, fixture.fundraiser_account.clone()),
+            (pledge, pledge_result.get_account(&pledge).unwrap().clone()),
+            (pledge_vault, pledge_result.get_account(&pledge_vault).unwrap().clone()),
+            (vault, fixture.vault_account.clone()),
+            (token_program, fixture.token_account.clone())
+        ],
+        &[Check::success()]
+    );
+
+    let fundraiser_data = &settle_result.get_account(&fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.current_amount.get(), 2_000_000);
+    assert_eq!(fundraiser_state.matched_amount.get(), 1_000_000);
+    let pledge_data = &settle_result.get_account(&pledge).unwrap().data;
+    let pledge_state = unsafe { load_acc::<MatchPledge>(pledge_data).unwrap() };
+    assert_eq!(pledge_state.matched.get(), 1_000_000);
+
+    // A second backer refunds on the last day before the crank runs, so
+    // there is nothing left to match
+    fixture.refresh(&settle_result);
+    let backer = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contribute_result = execute_contribute(&mollusk, &fixture, &backer, 1_000_000);
+    mollusk.sysvars.clock.unix_timestamp += SECONDS_TO_DAYS;
+    let refund_result = execute_refund(&mollusk, &contribute_result, Refund {
+        contributor: backer.wallet,
+        maker,
+        mint_to_raise,
+        rent_payer: None,
+    });
+    assert_eq!(refund_result.program_result, ProgramResult::Success);
+
+    let settle_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (mint_to_raise, refund_result.get_account(&mint_to_raise).unwrap().clone()),
+            (fundraiser, refund_result.get_account(&fundraiser).unwrap().clone()),
+            (pledge, settle_result.get_account(&pledge).unwrap().clone()),
+            (pledge_vault, settle_result.get_account(&pledge_vault).unwrap().clone()),
+            (vault, refund_result.get_account(&vault).unwrap().clone()),
+            (token_program, fixture.token_account.clone())
+        ],
+        &[Check::success()]
+    );
+    let fundraiser_data = &settle_result.get_account(&fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.current_amount.get(), 2_000_000);
+    assert_eq!(fundraiser_state.matched_amount.get(), 1_000_000);
+    let pledge_data = &settle_result.get_account(&pledge).unwrap().data;
+    let pledge_state = unsafe { load_acc::<MatchPledge>(pledge_data).unwrap() };
+    assert_eq!(pledge_state.matched.get(), 1_000_000);
+}
+
+#[test]
+fn test_contribute_for_beneficiary() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+
+    // The payer's tokens are credited to the beneficiary's PDA
+    let beneficiary = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let payer = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contributor = ContributorFixture {
+        contributor_acc: beneficiary.contributor_acc,
+        contributor_bump: beneficiary.contributor_bump,
+        contributor_acc_account: beneficiary.contributor_acc_account.clone(),
+        ..payer
+    };
+    let ContributorFixture { wallet: beneficiary, wallet_account: beneficiary_account, .. } =
+        beneficiary;
+    let contribute = Contribute {
+        beneficiary: Some(beneficiary),
+        ..Contribute::new(contributor.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+    };
+
+    // The gift counts towards the beneficiary's tier and cap, so it co-signs
+    let mut instruction = contribute.instruction();
+    let beneficiary_meta = instruction.accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == beneficiary)
+        .unwrap();
+    assert!(beneficiary_meta.is_signer);
+    beneficiary_meta.is_signer = false;
+    let mut tx_accounts = get_contribute_accounts(&mollusk, &fixture, &contributor, &contribute);
+    tx_accounts.push((beneficiary, beneficiary_account.clone()));
+    assert_eq!(
+        mollusk.process_instruction(&instruction, &tx_accounts).program_result,
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::MissingRequiredSignature)
+    );
+
+    let contribute_result = execute_contribute_with_accounts(
+        &mollusk,
+        &fixture,
+        &contributor,
+        contribute,
+        vec![(beneficiary, beneficiary_account)]
+    );
+    assert_eq!(contribute_result.program_result, ProgramResult::Success);
+
+    let contributor_data = &contribute_result
+        .get_account(&contributor.contributor_acc)
+        .unwrap().data;
+    let contributor_state = unsafe { load_acc::<Contributor>(contributor_data).unwrap() };
+    assert_eq!(contributor_state.owner, beneficiary.to_bytes());
+    assert_eq!(contributor_state.amount.get(), 1_000_000);
+
+    // Receipts go to the beneficiary too
+    let (receipt_ata, _) = get_receipt_ata(&mollusk, beneficiary, fixture.fundraiser, 0);
+    let receipt_ata_account = contribute_result.get_account(&receipt_ata).unwrap();
+    let receipt_ata_state = spl_token::state::Account::unpack(&receipt_ata_account.data).unwrap();
+    assert_eq!(receipt_ata_state.amount, 1_000_000);
+}
+
+#[test]
+fn test_weight_share() {
+    let mut contributor_state = Contributor::default();
+    contributor_state.initialize([1; 32], [1; 32]);
+    contributor_state.amount.set(3_000_000);
+    contributor_state.weight.set(3_600_000);
+    assert_eq!(contributor_state.weight_share(1_000_000), 1_200_000);
+    assert_eq!(contributor_state.weight_share(3_000_000), 3_600_000);
+}
+
+#[test]
+fn test_transfer_position() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+
+    let mut contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contribute_result = execute_contribute(&mollusk, &fixture, &contributor, 1_000_000);
+    fixture.refresh(&contribute_result);
+    contributor.refresh(&contribute_result);
+    let FundraiserFixture { fundraiser, system_program, token_program, .. } = fixture;
+
+    // Move 40% of the position to a new wallet
+    let recipient = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let (from_receipt_ata, _) = get_receipt_ata(&mollusk, contributor.wallet, fundraiser, 0);
+    let (to_receipt_ata, to_receipt_ata_account) = get_receipt_ata(
+        &mollusk,
+        recipient.wallet,
+        fundraiser,
+        0
+    );
+
+    let instruction = (TransferPosition {
+        owner: contributor.wallet,
+        maker: get_maker(&fixture.fundraiser_account),
+        recipient: recipient.wallet,
+        amount: 400_000,
+        rent_payer: None,
+        allowlist: None,
+    }).instruction();
+    let transfer_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (contributor.wallet, contributor.wallet_account.clone()),
+            (fundraiser, fixture.fundraiser_account.clone()),
+            (contributor.contributor_acc, contributor.contributor_acc_account.clone()),
+            (recipient.contributor_acc, recipient.contributor_acc_account),
+            (receipt_mint, contribute_result.get_account(&receipt_mint).unwrap().clone()),
+            (from_receipt_ata, contribute_result.get_account(&from_receipt_ata).unwrap().clone()),
+            (to_receipt_ata, to_receipt_ata_account),
+            (system_program, fixture.system_account.clone()),
+            (token_program, fixture.token_account.clone())
+        ],
+        &[Check::success()]
+    );
+
+    let from_data = &transfer_result.get_account(&contributor.contributor_acc).unwrap().data;
+    let from_state = unsafe { load_acc::<Contributor>(from_data).unwrap() };
+    assert_eq!(from_state.amount.get(), 600_000);
+    let to_data = &transfer_result.get_account(&recipient.contributor_acc).unwrap().data;
+    let to_state = unsafe { load_acc::<Contributor>(to_data).unwrap() };
+    assert_eq!(to_state.owner, recipient.wallet.to_bytes());
+    assert_eq!(to_state.amount.get(), 400_000);
+
+    let to_receipt_ata_account = transfer_result.get_account(&to_receipt_ata).unwrap();
+    let to_receipt_state = spl_token::state::Account::unpack(&to_receipt_ata_account.data).unwrap();
+    assert_eq!(to_receipt_state.amount, 400_000);
+
+    // A recipient already at the per-wallet cap can't receive more
+    fixture.refresh(&transfer_result);
+    let capped = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let capped_result = execute_contribute(&mollusk, &fixture, &capped, 1_000_000);
+    assert_eq!(capped_result.program_result, ProgramResult::Success);
+    let (capped_receipt_ata, _) = get_receipt_ata(&mollusk, capped.wallet, fundraiser, 0);
+    let instruction = (TransferPosition {
+        owner: contributor.wallet,
+        maker: fixture.maker,
+        recipient: capped.wallet,
+        amount: 100_000,
+        rent_payer: None,
+        allowlist: None,
+    }).instruction();
+    let capped_transfer_result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (contributor.wallet, transfer_result.get_account(&contributor.wallet).unwrap().clone()),
+            (fundraiser, capped_result.get_account(&fundraiser).unwrap().clone()),
+            (
+                contributor.contributor_acc,
+                transfer_result.get_account(&contributor.contributor_acc).unwrap().clone(),
+            ),
+            (
+                capped.contributor_acc,
+                capped_result.get_account(&capped.contributor_acc).unwrap().clone(),
+            ),
+            (receipt_mint, capped_result.get_account(&receipt_mint).unwrap().clone()),
+            (from_receipt_ata, transfer_result.get_account(&from_receipt_ata).unwrap().clone()),
+            (
+                capped_receipt_ata,
+                capped_result.get_account(&capped_receipt_ata).unwrap().clone(),
+            ),
+            (system_program, fixture.system_account.clone()),
+            (token_program, fixture.token_account.clone()),
+        ]
+    );
+    assert_eq!(
+        capped_transfer_result.program_result,
+        ProgramResult::Failure(
+            solana_sdk::program_error::ProgramError::Custom(
+                FundraiserError::MaximumContributionsReached as u32
+            )
+        )
+    );
+}
+
+#[test]
+fn test_contribute_with_fee_payer() {
+    let mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+
+    let contributor = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let contributor_lamports = contributor.wallet_account.lamports;
+
+    // The fee payer funds the contributor account rent
+    let fee_payer = Pubkey::new_unique();
+    let fee_payer_account = Account::new(LAMPORTS_PER_SOL, 0, &fixture.system_program);
+    let contribute_result = execute_contribute_with_accounts(
+        &mollusk,
+        &fixture,
+        &contributor,
+        Contribute {
+            fee_payer: Some(fee_payer),
+            ..Contribute::new(contributor.wallet, fixture.maker, fixture.mint_to_raise, 1_000_000)
+        },
+        vec![(fee_payer, fee_payer_account)]
+    );
+    assert_eq!(contribute_result.program_result, ProgramResult::Success);
+
+    let contributor_data = &contribute_result
+        .get_account(&contributor.contributor_acc)
+        .unwrap().data;
+    let contributor_state = unsafe { load_acc::<Contributor>(contributor_data).unwrap() };
+    assert_eq!(contributor_state.rent_payer, fee_payer.to_bytes());
+    assert_eq!(
+        contribute_result.get_account(&contributor.wallet).unwrap().lamports,
+        contributor_lamports
+    );
+    assert_eq!(
+        contribute_result.get_account(&fee_payer).unwrap().lamports,
+        LAMPORTS_PER_SOL - mollusk.sysvars.rent.minimum_balance(Contributor::LEN)
+    );
+}
+
+#[test]
+fn test_registry_entries() {
+    let mut buffer = vec![0u8; ContributorRegistry::space(3) + 1];
+    // Entries have no alignment requirement
+    let bytes = &mut buffer[1..];
+    let header = unsafe {
+        load_acc_mut_unchecked::<ContributorRegistry>(&mut bytes[..ContributorRegistry::LEN])
+    };
+    header.unwrap().initialize([9; 32], 255);
+
+    // Entries are kept sorted whatever the insertion order
+    for (len, key) in [[3u8; 32], [1u8; 32], [2u8; 32]].into_iter().enumerate() {
+        let space = ContributorRegistry::space(len + 1);
+        let (_, entries) = unsafe {
+            ContributorRegistry::load_mut(&mut bytes[..ContributorRegistry::space(len)]).unwrap()
+        };
+        let index = ContributorRegistry::find(entries, &key).unwrap_err();
+        unsafe {
+            ContributorRegistry::insert(&mut bytes[..space], index, RegistryEntry {
+                contributor: key,
+                amount: PodU64::new(key[0] as u64),
+            }).unwrap();
+        }
+    }
+    let (header, entries) = unsafe { ContributorRegistry::load_mut(bytes).unwrap() };
+    assert_eq!(header.entry_count.get(), 3);
+    assert_eq!(
+        entries.iter().map(|entry| entry.amount.get()).collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    assert_eq!(ContributorRegistry::find(entries, &[2; 32]), Ok(1));
+
+    unsafe {
+        ContributorRegistry::remove(bytes, 1).unwrap();
+    }
+    let (_, entries) = unsafe {
+        ContributorRegistry::load_mut(&mut bytes[..ContributorRegistry::space(2)]).unwrap()
+    };
+    assert_eq!(
+        entries.iter().map(|entry| entry.amount.get()).collect::<Vec<_>>(),
+        [1, 3]
+    );
+}
+
+#[test]
+fn test_contribute_registry() {
+    let mut mollusk = mollusk();
+    let mut fixture = setup_fundraiser(&mollusk);
+    let init_result = execute_initialize(&mollusk, &fixture, 10_000_000, 1);
+    fixture.refresh(&init_result);
+    let FundraiserFixture {
+        maker,
+        fundraiser,
+        mint_to_raise,
+        vault,
+        system_program,
+        token_program,
+        ..
+    } = fixture;
+
+    // Switch the fundraiser to the registry
+    let (registry, _) = find_registry_address(&fundraiser);
+    let instruction = (InitializeRegistry { maker, capacity: 1 }).instruction();
+    let registry_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (maker, fixture.maker_account.clone()),
+            (fundraiser, fixture.fundraiser_account.clone()),
+            (registry, Account::new(0, 0, &system_program)),
+            (system_program, fixture.system_account.clone())
+        ],
+        &[Check::success()]
+    );
+
+    let ContributorFixture {
+        wallet: contributor,
+        wallet_account: contributor_account,
+        ata: contributor_ata,
+        ata_account: contributor_ata_account,
+        ..
+    } = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let (receipt_ata, receipt_ata_account) = get_receipt_ata(&mollusk, contributor, fundraiser, 0);
+
+    let instruction = (ContributeRegistry {
+        contributor,
+        maker,
+        mint_to_raise,
+        amount: 1_000_000,
+        allowlist: None,
+    }).instruction();
+    let contribute_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (contributor, contributor_account),
+            (mint_to_raise, fixture.mint_to_raise_account.clone()),
+            (fundraiser, registry_result.get_account(&fundraiser).unwrap().clone()),
+            (registry, registry_result.get_account(&registry).unwrap().clone()),
+            (contributor_ata, contributor_ata_account),
+            (vault, fixture.vault_account.clone()),
+            (token_program, fixture.token_account.clone()),
+            (system_program, fixture.system_account.clone()),
+            (receipt_mint, get_receipt_mint_account(&mollusk, fundraiser, 0)),
+            (receipt_ata, receipt_ata_account)
+        ],
+        &[Check::success()]
+    );
+
+    let contributed = Contributed {
+        fundraiser: fundraiser.to_bytes(),
+        contributor: contributor.to_bytes(),
+        amount: 1_000_000,
+        current_amount: 1_000_000,
+    };
+    assert_eq!(take_events(&mut mollusk).last(), Some(&FundraiserEvent::Contributed(contributed)));
+
+    let registry_account = contribute_result.get_account(&registry).unwrap();
+    assert_eq!(registry_account.data.len(), ContributorRegistry::space(1));
+    let mut registry_data = registry_account.data.clone();
+    let (header, entries) = unsafe { ContributorRegistry::load_mut(&mut registry_data).unwrap() };
+    assert_eq!(header.entry_count.get(), 1);
+    assert_eq!(entries[0].contributor, contributor.to_bytes());
+    assert_eq!(entries[0].amount.get(), 1_000_000);
+    // The entry rent came from the maker, not the backer
+    assert_eq!(
+        registry_account.lamports,
+        mollusk.sysvars.rent.minimum_balance(registry_account.data.len())
+    );
+
+    // The maker only prepaid one entry
+    let ContributorFixture {
+        wallet: other,
+        ata: other_ata,
+        ata_account: other_ata_account,
+        ..
+    } = setup_contributor(&mollusk, &fixture, Pubkey::new_unique());
+    let (other_receipt_ata, other_receipt_ata_account) = get_receipt_ata(
+        &mollusk,
+        other,
+        fundraiser,
+        0
+    );
+    let instruction = (ContributeRegistry {
+        contributor: other,
+        maker,
+        mint_to_raise,
+        amount: 1_000_000,
+        allowlist: None,
+    }).instruction();
+    let full_result = mollusk.process_instruction(
+        &instruction,
+        &[
+            (other, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (mint_to_raise, contribute_result.get_account(&mint_to_raise).unwrap().clone()),
+            (fundraiser, contribute_result.get_account(&fundraiser).unwrap().clone()),
+            (registry, registry_account.clone()),
+            (other_ata, other_ata_account),
+            (vault, contribute_result.get_account(&vault).unwrap().clone()),
+            (token_program, contribute_result.get_account(&token_program).unwrap().clone()),
+            (system_program, contribute_result.get_account(&system_program).unwrap().clone()),
+            (receipt_mint, contribute_result.get_account(&receipt_mint).unwrap().clone()),
+            (other_receipt_ata, other_receipt_ata_account)
+        ]
+    );
+    assert_eq!(
+        full_result.program_result,
+        ProgramResult::Failure(
+            solana_sdk::program_error::ProgramError::Custom(FundraiserError::RegistryFull as u32)
+        )
+    );
+
+    // The campaign misses its target, the backer is refunded from the registry
+    mollusk.sysvars.clock.unix_timestamp += 2 * SECONDS_TO_DAYS;
+    let instruction = (RefundRegistry { contributor, maker, mint_to_raise }).instruction();
+    let tx_accounts: Vec<_> = instruction.accounts
+        .iter()
+        .map(|meta| (meta.pubkey, contribute_result.get_account(&meta.pubkey).unwrap().clone()))
+        .collect();
+    let refund_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &tx_accounts,
+        &[Check::success()]
+    );
+    let refunded = Refunded {
+        fundraiser: fundraiser.to_bytes(),
+        contributor: contributor.to_bytes(),
+        amount: 1_000_000,
+    };
+    // The failed contribution of the other backer logged nothing
+    assert_eq!(take_events(&mut mollusk), vec![FundraiserEvent::Refunded(refunded)]);
+
+    let contributor_ata_account = refund_result.get_account(&contributor_ata).unwrap();
+    let contributor_ata_state = spl_token::state::Account::unpack(
+        &contributor_ata_account.data
+    ).unwrap();
+    assert_eq!(contributor_ata_state.amount, 1_000_000);
+    let receipt_ata_account = refund_result.get_account(&receipt_ata).unwrap();
+    let receipt_ata_state = spl_token::state::Account::unpack(&receipt_ata_account.data).unwrap();
+    assert_eq!(receipt_ata_state.amount, 0);
+
+    // The entry is removed, its rent stays in the registry for the maker
+    let refunded_registry = refund_result.get_account(&registry).unwrap();
+    assert_eq!(refunded_registry.data.len(), ContributorRegistry::space(0));
+    assert_eq!(refunded_registry.lamports, registry_account.lamports);
+    assert_eq!(
+        refund_result.get_account(&contributor).unwrap().lamports,
+        contribute_result.get_account(&contributor).unwrap().lamports
+    );
+    let mut registry_data = refunded_registry.data.clone();
+    let (header, _) = unsafe { ContributorRegistry::load_mut(&mut registry_data).unwrap() };
+    assert_eq!(header.entry_count.get(), 0);
+
+    // The totals match those of a plain refund
+    let fundraiser_data = &refund_result.get_account(&fundraiser).unwrap().data;
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.contributor_count.get(), 0);
+    assert_eq!(fundraiser_state.current_amount.get(), 0);
+    assert_eq!(fundraiser_state.total_weight.get(), 0);
+}
+
+#[test]
+fn test_account_discriminator() {
+    let mut fundraiser_state = Fundraiser::default();
+    assert_eq!(
+        unsafe { load_acc::<Fundraiser>(to_bytes(&fundraiser_state)) }.unwrap_err(),
+        ProgramError::UninitializedAccount
+    );
+
+    fundraiser_state.initialize([1; 32], [2; 32], 10_000_000, 1, 255, 0);
+    let mut fundraiser_data = unsafe { to_bytes(&fundraiser_state) }.to_vec();
+    assert!(unsafe { load_acc::<Fundraiser>(&fundraiser_data) }.is_ok());
+    assert_eq!(fundraiser_data[0], AccountDiscriminator::Fundraiser as u8);
+
+    // Another account type with the same length
+    fundraiser_data[0] = AccountDiscriminator::Round as u8;
+    assert_eq!(
+        unsafe { load_acc::<Fundraiser>(&fundraiser_data) }.unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+
+    // A layout version the program does not know
+    fundraiser_data[0] = AccountDiscriminator::Fundraiser as u8;
+    fundraiser_data[1] = Fundraiser::VERSION + 1;
+    assert_eq!(
+        unsafe { load_acc::<Fundraiser>(&fundraiser_data) }.unwrap_err(),
+        FundraiserError::InvalidAccountVersion.into()
+    );
+}
+
+#[test]
+fn test_account_decoders() {
+    let day = SECONDS_TO_DAYS;
+    let start = 1_000 * day;
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.initialize([1; 32], [2; 32], 10_000_000, 2, 254, start);
+    fundraiser_state.add_tier(1_000_000, 10, 7);
+    fundraiser_state.current_amount.set(2_500_000);
+    fundraiser_state.contributor_count.set(3);
+
+    let fundraiser = FundraiserAccount::unpack(unsafe { to_bytes(&fundraiser_state) }).unwrap();
+    assert_eq!(fundraiser.maker, Pubkey::new_from_array([1; 32]));
+    assert_eq!(fundraiser.amount_to_raise, 10_000_000);
+    assert_eq!(fundraiser.current_amount, 2_500_000);
+    assert_eq!(fundraiser.time_started, start);
+    assert_eq!(fundraiser.bump, 254);
+    assert_eq!(fundraiser.tiers.len(), 1);
+    assert_eq!(fundraiser.tiers[0].label_id, 7);
+    assert!(fundraiser.bonus_periods.is_empty());
+    assert_eq!(fundraiser.receipt_mint, None);
+    assert_eq!(fundraiser.round, None);
+    assert_eq!(fundraiser.contributor_count, 3);
+    assert_eq!(fundraiser.progress_percent(), 25);
+
+    // The status and windows follow the on-chain rules, the last day
+    // accepts contributions and refunds alike
+    let times = [
+        start - 1,
+        start,
+        start + 2 * day - 1,
+        start + 2 * day,
+        start + 3 * day,
+        start + 300 * day,
+    ];
+    for now in times {
+        assert_eq!(fundraiser.status(now), fundraiser_state.status(now));
+        assert_eq!(fundraiser.is_open(now), fundraiser_state.is_open(now));
+        assert_eq!(fundraiser_state.is_ended(now), !fundraiser_state.is_open(now));
+        assert_eq!(fundraiser.is_refund_window(now), fundraiser_state.is_refund_window(now));
+    }
+    assert_eq!(fundraiser.status(start - 1), CampaignStatus::NotStarted);
+    assert_eq!(fundraiser.status(start + 2 * day), CampaignStatus::Active);
+    assert!(fundraiser.is_refund_window(start + 2 * day));
+    assert_eq!(fundraiser.status(start + 3 * day), CampaignStatus::Failed);
+    // Long expired campaigns stay closed instead of wrapping around
+    assert!(fundraiser_state.is_ended(start + 300 * day));
+    assert!(fundraiser_state.is_refund_window(start + 300 * day));
+    assert_eq!(fundraiser.status(start + 300 * day), CampaignStatus::Failed);
+    assert_eq!(fundraiser.time_remaining(start + day), 2 * day);
+    assert_eq!(fundraiser.time_remaining(start + 3 * day), 0);
+
+    // A wallet under the cap can still add one full contribution
+    assert_eq!(fundraiser.max_contribution(), fundraiser_state.max_contribution());
+    assert_eq!(fundraiser.remaining_allowance(1_000_000, start), 1_000_000);
+    assert_eq!(fundraiser.remaining_allowance(1_500_000, start), 0);
+    assert_eq!(fundraiser.remaining_allowance(0, start + 3 * day), 0);
+
+    fundraiser_state.current_amount.set(10_000_000);
+    let fundraiser = FundraiserAccount::unpack(unsafe { to_bytes(&fundraiser_state) }).unwrap();
+    assert_eq!(fundraiser.status(start + 3 * day), CampaignStatus::Succeeded);
+    fundraiser_state.settled.set(true);
+    let fundraiser = FundraiserAccount::unpack(unsafe { to_bytes(&fundraiser_state) }).unwrap();
+    assert_eq!(fundraiser.status(start), CampaignStatus::Finalized);
+    assert_eq!(fundraiser.remaining_allowance(0, start), 0);
+
+    let mut contributor_state = Contributor::default();
+    contributor_state.initialize([3; 32], [4; 32]);
+    contributor_state.amount.set(1_000_000);
+    let contributor_data = unsafe { to_bytes(&contributor_state) };
+    let contributor = ContributorAccount::unpack(contributor_data).unwrap();
+    assert_eq!(contributor.owner, Pubkey::new_from_array([3; 32]));
+    assert_eq!(contributor.rent_payer, Pubkey::new_from_array([4; 32]));
+    assert_eq!(contributor.amount, 1_000_000);
+    assert_eq!(contributor.tier, None);
+
+    // Accounts of another type, length or version are rejected
+    assert_eq!(
+        FundraiserAccount::unpack(contributor_data).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+    let mut contributor_data = contributor_data.to_vec();
+    contributor_data[1] = Contributor::VERSION + 1;
+    assert_eq!(
+        ContributorAccount::unpack(&contributor_data).unwrap_err(),
+        FundraiserError::InvalidAccountVersion.into()
+    );
+    contributor_data[0] = AccountDiscriminator::Fundraiser as u8;
+    assert_eq!(
+        ContributorAccount::unpack(&contributor_data).unwrap_err(),
+        ProgramError::InvalidAccountData
+    );
+}
+
+/// Size and alignment of an IDL type, with the field alignment dropped for
+/// packed (and Borsh) layouts
+fn idl_type_layout(
+    ty: &serde_json::Value,
+    types: &[serde_json::Value],
+    packed: bool
+) -> (usize, usize) {
+    let (size, align) = match ty {
+        serde_json::Value::String(name) => match name.as_str() {
+            "u8" | "bool" => (1, 1),
+            "u16" => (2, 2),
+            "u32" => (4, 4),
+            "u64" | "i64" => (8, 8),
+            "pubkey" => (32, 1),
+            other => panic!("unexpected type {other}"),
+        }
+        _ if ty.get("array").is_some() => {
+            let (size, align) = idl_type_layout(&ty["array"][0], types, packed);
+            (size * (ty["array"][1].as_u64().unwrap() as usize), align)
+        }
+        _ => {
+            let name = &ty["defined"]["name"];
+            let def = types.iter().find(|def| def["name"] == *name).unwrap();
+            // Enums without fields encode as a one byte variant index
+            if def["type"]["kind"] == "enum" {
+                (1, 1)
+            } else {
+                idl_struct_layout(def, types, packed, 0)
+            }
+        }
+    };
+    (size, if packed { 1 } else { align })
+}
+
+/// C layout of an IDL struct after `prefix` leading bytes
+fn idl_struct_layout(
+    def: &serde_json::Value,
+    types: &[serde_json::Value],
+    packed: bool,
+    prefix: usize
+) -> (usize, usize) {
+    let packed = packed || def["repr"]["packed"] == true;
+    let (mut offset, mut max_align) = (prefix, 1);
+    for field in def["type"]["fields"].as_array().unwrap() {
+        let (size, align) = idl_type_layout(&field["type"], types, packed);
+        offset = offset.next_multiple_of(align) + size;
+        max_align = max_align.max(align);
+    }
+    (offset.next_multiple_of(max_align), max_align)
+}
+
+#[test]
+fn test_idl_in_sync() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/idl/pinocchio_fundraiser.json");
+    let generated = idl_json();
+    if std::env::var_os("UPDATE_IDL").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(path).parent().unwrap()).unwrap();
+        std::fs::write(path, &generated).unwrap();
+    }
+    let checked_in = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(
+        checked_in == generated,
+        "idl/pinocchio_fundraiser.json is stale, regenerate it with \
+         `UPDATE_IDL=1 cargo test --features test-default test_idl_in_sync`"
+    );
+}
+
+#[test]
+fn test_idl_matches_program() {
+    let idl = idl();
+    let types = idl["types"].as_array().unwrap();
+
+    // Every builder with its optional accounts, only the initialize metadata
+    // is appended to the data
+    let key = Pubkey::new_unique;
+    let (maker, sponsor, mint_to_raise) = (key(), key(), key());
+    let (contributor, recipient, authority) = (key(), key(), key());
+    let (rent_payer, payer) = (key(), key());
+    let (fundraiser, _) = find_fundraiser_address(&maker);
+    let (round, _) = find_round_address(&authority, 1);
+    let builders = [
+        (Initialize {
+            maker,
+            mint_to_raise,
+            params: InitializeIxData::default(),
+            metadata: Some(get_metadata_ix_data("Test", 0)),
+        }).instruction(),
+        (Contribute {
+            round: Some(round),
+            beneficiary: Some(recipient),
+            fee_payer: Some(rent_payer),
+            ..Contribute::new(contributor, maker, mint_to_raise, 1)
+        }).instruction(),
+        (CheckContribution { maker, mint_to_raise, uses_registry: true }).instruction(),
+        (Refund {
+            contributor,
+            maker,
+            mint_to_raise,
+            rent_payer: Some(rent_payer),
+        }).instruction(),
+        (UpdateMetadata { maker, metadata: get_metadata_ix_data("Test", 0) }).instruction(),
+        (InitializeRound {
+            authority,
+            mint: mint_to_raise,
+            id: 1,
+            end_time: 0,
+        }).instruction(),
+        (JoinRound { maker, authority, round_id: 1 }).instruction(),
+        (DistributeRound {
+            authority,
+            round_id: 1,
+            mint: mint_to_raise,
+            maker,
+            index: 0,
+        }).instruction(),
+        (CreateMatchPledge { sponsor, maker, mint_to_raise, cap: 1 }).instruction(),
+        (SettleMatch { sponsor, maker, mint_to_raise }).instruction(),
+        (ReleaseMatch { sponsor, maker, mint_to_raise }).instruction(),
+        (TransferPosition {
+            owner: contributor,
+            maker,
+            recipient,
+            amount: 1,
+            rent_payer: Some(rent_payer),
+            allowlist: None,
+        }).instruction(),
+        (CloseContributor { contributor, maker, rent_payer: Some(rent_payer) }).instruction(),
+        (InitializeRegistry { maker, capacity: 1 }).instruction(),
+        (ContributeRegistry {
+            contributor,
+            maker,
+            mint_to_raise,
+            amount: 1,
+            allowlist: None,
+        }).instruction(),
+        (RefundRegistry { contributor, maker, mint_to_raise }).instruction(),
+        (Migrate {
+            payer,
+            account: find_contributor_address(&fundraiser, &contributor).0,
+            contributor_seeds: Some((fundraiser, contributor)),
+        }).instruction(),
+        (GetStatus { maker, contributor: Some(contributor) }).instruction(),
+    ];
+
+    // The processor behind each builder with the fixed length of its data
+    type Processor = fn(
+        &[pinocchio::account_info::AccountInfo],
+        &[u8]
+    ) -> pinocchio::ProgramResult;
+    let processors: [(Processor, usize); 18] = [
+        (processor::process_initialize, InitializeIxData::LEN),
+        (processor::process_contribute, ContributeIxData::LEN),
+        (processor::process_check_contribution, 0),
+        (processor::process_refund, RefundIxData::LEN),
+        (processor::process_update_metadata, MetadataIxData::LEN),
+        (processor::process_initialize_round, InitializeRoundIxData::LEN),
+        (processor::process_join_round, 0),
+        (processor::process_distribute_round, DistributeRoundIxData::LEN),
+        (processor::process_create_match_pledge, CreateMatchPledgeIxData::LEN),
+        (processor::process_settle_match, 0),
+        (processor::process_release_match, 0),
+        (processor::process_transfer_position, TransferPositionIxData::LEN),
+        (processor::process_close_contributor, CloseContributorIxData::LEN),
+        (processor::process_initialize_registry, InitializeRegistryIxData::LEN),
+        (processor::process_contribute_registry, ContributeRegistryIxData::LEN),
+        (processor::process_refund_registry, 0),
+        (processor::process_migrate, MigrateIxData::LEN),
+        (processor::process_get_status, GetStatusIxData::LEN),
+    ];
+
+    // Address each IDL account name stands for with the keys above
+    let (receipt_mint, _) = find_receipt_mint_address(&fundraiser);
+    let (pledge, _) = find_match_pledge_address(&fundraiser, &sponsor);
+    let (contributor_acc, _) = find_contributor_address(&fundraiser, &contributor);
+    let (recipient_acc, _) = find_contributor_address(&fundraiser, &recipient);
+    let ata = |owner: &Pubkey, mint: &Pubkey| get_associated_token_address(owner, mint);
+    let expected_addresses = |name: &str| -> Vec<Pubkey> {
+        match name {
+            "maker" => vec![maker],
+            "fundraiser" => vec![fundraiser],
+            "mint_to_raise" | "mint" => vec![mint_to_raise],
+            "contributor" | "owner" => vec![contributor],
+            // Contributions are a gift to the recipient, credited to its accounts
+            "contributor_acc" | "from_contributor_acc" | "account" =>
+                vec![contributor_acc, recipient_acc],
+            "to_contributor_acc" => vec![recipient_acc],
+            "contributor_ata" => vec![ata(&contributor, &mint_to_raise)],
+            "contributor_receipt_ata" | "from_receipt_ata" =>
+                vec![ata(&contributor, &receipt_mint), ata(&recipient, &receipt_mint)],
+            "to_receipt_ata" => vec![ata(&recipient, &receipt_mint)],
+            "beneficiary" => vec![recipient],
+            "maker_ata" => vec![ata(&maker, &mint_to_raise)],
+            // The fundraiser vault, or the matching pool of round instructions
+            "vault" => vec![ata(&fundraiser, &mint_to_raise), ata(&round, &mint_to_raise)],
+            "receipt_mint" => vec![receipt_mint],
+            "metadata" => vec![find_metadata_address(&fundraiser).0],
+            "round" => vec![round],
+            "authority" => vec![authority],
+            "sponsor" => vec![sponsor],
+            "sponsor_ata" => vec![ata(&sponsor, &mint_to_raise)],
+            "pledge" => vec![pledge],
+            "pledge_vault" => vec![ata(&pledge, &mint_to_raise)],
+            "registry" => vec![find_registry_address(&fundraiser).0],
+            "rent_payer" | "fee_payer" => vec![rent_payer],
+            "payer" => vec![payer],
+            _ => panic!("no address for account {name}"),
+        }
+    };
+
+    let instructions = idl["instructions"].as_array().unwrap();
+    assert_eq!(instructions.len(), builders.len());
+
+    let cases = instructions.iter().zip(builders).zip(processors);
+    for ((ix, builder), (process, data_len)) in cases {
+        let name = ix["name"].as_str().unwrap();
+        assert_eq!(ix["discriminator"][0], builder.data[0], "{name}");
+
+        // Names, order and flags follow the client builders
+        let accounts = ix["accounts"].as_array().unwrap();
+        assert_eq!(accounts.len(), builder.accounts.len(), "{name}");
+        for (account, meta) in accounts.iter().zip(&builder.accounts) {
+            let account_name = account["name"].as_str().unwrap();
+            assert_eq!(account["writable"] == true, meta.is_writable, "{name}.{account_name}");
+            assert_eq!(account["signer"] == true, meta.is_signer, "{name}.{account_name}");
+            match account.get("address") {
+                Some(address) =>
+                    assert_eq!(*address, meta.pubkey.to_string(), "{name}.{account_name}"),
+                None =>
+                    assert!(
+                        expected_addresses(account_name).contains(&meta.pubkey),
+                        "{name}.{account_name}"
+                    ),
+            }
+        }
+        // Optional accounts trail the required ones
+        let first_optional = accounts
+            .iter()
+            .position(|account| account["optional"] == true)
+            .unwrap_or(accounts.len());
+        assert!(
+            accounts[first_optional..].iter().all(|account| account["optional"] == true),
+            "{name}"
+        );
+
+        let args_len: usize = ix["args"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|arg| idl_type_layout(&arg["type"], types, true).0)
+            .sum();
+        let tail_len = if name == "initialize" { MetadataIxData::LEN } else { 0 };
+        assert_eq!(1 + args_len + tail_len, builder.data.len(), "{name}");
+        assert_eq!(args_len, data_len, "{name}");
+
+        // The processor takes exactly the required accounts: one short and it
+        // rejects the list, with all of them it gets on to the signer and
+        // owner checks of these bare accounts
+        let (_buffers, infos) = cpi_account_infos(&builder);
+        assert_eq!(
+            process(&infos[..first_optional - 1], &builder.data[1..]),
+            Err(ProgramError::InvalidAccountData),
+            "{name}"
+        );
+        assert_ne!(
+            process(&infos[..first_optional], &builder.data[1..]),
+            Err(ProgramError::InvalidAccountData),
+            "{name}"
+        );
+    }
+    let get_status = instructions.iter().find(|ix| ix["name"] == "get_status").unwrap();
+    assert_eq!(idl_type_layout(&get_status["returns"], types, true).0, FundraiserStatus::LEN);
+
+    // State layouts match the account sizes, discriminator included
+    let state_lens = [
+        Fundraiser::LEN,
+        Contributor::LEN,
+        FundraiserMetadata::LEN,
+        Round::LEN,
+        MatchPledge::LEN,
+        ContributorRegistry::LEN,
+    ];
+    let accounts = idl["accounts"].as_array().unwrap();
+    assert_eq!(accounts.len(), state_lens.len());
+    for (account, len) in accounts.iter().zip(state_lens) {
+        let def = types.iter().find(|def| def["name"] == account["name"]).unwrap();
+        assert_eq!(idl_struct_layout(def, types, false, 1).0, len, "{}", account["name"]);
+    }
+    let entry = types.iter().find(|def| def["name"] == "RegistryEntry").unwrap();
+    assert_eq!(idl_struct_layout(entry, types, false, 0).0, RegistryEntry::LEN);
+
+    // Events follow the wire format behind their discriminator
+    let event_lens = [
+        FundraiserCreated::LEN,
+        Contributed::LEN,
+        Refunded::LEN,
+        Finalized::LEN,
+        Cancelled::LEN,
+        Matched::LEN,
+    ];
+    let events = idl["events"].as_array().unwrap();
+    assert_eq!(events.len(), event_lens.len());
+    for (event, len) in events.iter().zip(event_lens) {
+        let def = types.iter().find(|def| def["name"] == event["name"]).unwrap();
+        assert_eq!(idl_struct_layout(def, types, true, 0).0, len, "{}", event["name"]);
+    }
+
+    // Errors are listed by code with their message
+    let errors = idl["errors"].as_array().unwrap();
+    for (code, error) in errors.iter().enumerate() {
+        let variant = FundraiserError::try_from(code as u32).unwrap();
+        assert_eq!(error["code"], variant as u32);
+        assert_eq!(error["name"], format!("{variant:?}"));
+        assert_eq!(error["msg"], variant.message());
+    }
+    assert!(FundraiserError::try_from(errors.len() as u32).is_err());
+}
+
+#[test]
+fn test_migrate_legacy_layouts() {
+    // Legacy accounts are told apart by length, it must not match a current layout
+    let current_lens = [
+        Fundraiser::LEN,
+        Contributor::LEN,
+        FundraiserMetadata::LEN,
+        Round::LEN,
+        MatchPledge::LEN,
+        ContributorRegistry::LEN,
+    ];
+    for legacy_len in [FundraiserV0::LEN, ContributorV0::LEN] {
+        assert!(!current_lens.contains(&legacy_len));
+    }
+
+    let fundraiser_v0 = FundraiserV0 {
+        is_initialized: 1,
+        maker: [1; 32],
+        mint_to_raise: [2; 32],
+        amount_to_raise: 10_000_000,
+        current_amount: 4_000_000,
+        time_started: 1_700_000_000,
+        duration: 7,
+        bump: 254,
+    };
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_v0.migrate(&mut fundraiser_state);
+    let fundraiser_data = unsafe { to_bytes(&fundraiser_state) };
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(fundraiser_data).unwrap() };
+    assert_eq!(fundraiser_state.maker, [1; 32]);
+    assert_eq!(fundraiser_state.mint_to_raise, [2; 32]);
+    assert_eq!(fundraiser_state.amount_to_raise.get(), 10_000_000);
+    assert_eq!(fundraiser_state.current_amount.get(), 4_000_000);
+    assert_eq!(fundraiser_state.total_weight.get(), 4_000_000);
+    assert_eq!(fundraiser_state.time_started.get(), 1_700_000_000);
+    assert_eq!(fundraiser_state.duration, 7);
+    assert_eq!(fundraiser_state.bump, 254);
+    assert!(!fundraiser_state.has_receipts());
+    assert_eq!(fundraiser_state.contributor_count.get(), 0);
+
+    let contributor_v0 = ContributorV0 { is_initialized: 1, amount: 1_000_000 };
+    let mut contributor_state = Contributor::default();
+    contributor_v0.migrate(&mut contributor_state, [3; 32]);
+    let contributor_data = unsafe { to_bytes(&contributor_state) };
+    let contributor_state = unsafe { load_acc::<Contributor>(contributor_data).unwrap() };
+    assert_eq!(contributor_state.owner, [3; 32]);
+    assert_eq!(contributor_state.rent_payer, [3; 32]);
+    assert_eq!(contributor_state.amount.get(), 1_000_000);
+    assert_eq!(contributor_state.weight.get(), 1_000_000);
+    assert!(!contributor_state.has_tier());
+}
+
+#[test]
+fn test_migrate_fundraiser() {
+    let mollusk = mollusk();
+    let payer = Pubkey::new_unique();
+    let fundraiser = Pubkey::new_unique();
+    let (system_program, system_account) = program::keyed_account_for_system_program();
+
+    // Fixture of a fundraiser written by the first program version
+    let fundraiser_v0 = FundraiserV0 {
+        is_initialized: 1,
+        maker: Pubkey::new_unique().to_bytes(),
+        mint_to_raise: Pubkey::new_unique().to_bytes(),
+        amount_to_raise: 10_000_000,
+        current_amount: 2_000_000,
+        time_started: 0,
+        duration: 1,
+        bump: 255,
+    };
+    let mut fundraiser_account = Account::new(
+        mollusk.sysvars.rent.minimum_balance(FundraiserV0::LEN),
+        FundraiserV0::LEN,
+        &PROGRAM
+    );
+    fundraiser_account.data = unsafe { to_bytes(&fundraiser_v0) }.to_vec();
+
+    let instruction = (Migrate {
+        payer,
+        account: fundraiser,
+        contributor_seeds: None,
+    }).instruction();
+    let migrate_result = mollusk.process_and_validate_instruction(
+        &instruction,
+        &[
+            (payer, Account::new(LAMPORTS_PER_SOL, 0, &system_program)),
+            (fundraiser, fundraiser_account),
+            (system_program, system_account)
+        ],
+        &[Check::success()]
+    );
+
+    let fundraiser_account = migrate_result.get_account(&fundraiser).unwrap();
+    assert_eq!(fundraiser_account.data.len(), Fundraiser::LEN);
+    assert_eq!(fundraiser_account.lamports, mollusk.sysvars.rent.minimum_balance(Fundraiser::LEN));
+    let fundraiser_state = unsafe { load_acc::<Fundraiser>(&fundraiser_account.data).unwrap() };
+    assert_eq!(fundraiser_state.maker, fundraiser_v0.maker);
+    assert_eq!(fundraiser_state.current_amount.get(), 2_000_000);
+    // Legacy campaigns that raised anything keep their metadata locked
+    assert!(fundraiser_state.metadata_locked.get());
+}
+
+#[test]
+fn test_ix_data_wire_format() {
+    // Contribute: fields back to back, no padding
+    let contribute = ContributeIxData {
+        amount: 1_000_000,
+        fundraiser_bump: 254,
+        contributor_bump: 253,
+        tier: NO_TIER,
+        beneficiary: [9; 32],
+    };
+    let data = contribute.pack();
+    assert_eq!(data.len(), ContributeIxData::LEN);
+    assert_eq!(data[..8], 1_000_000u64.to_le_bytes());
+    assert_eq!(data[8..11], [254, 253, NO_TIER]);
+    assert_eq!(data[11..], [9; 32]);
+    assert_eq!(ContributeIxData::unpack(&data).unwrap(), contribute);
+
+    // The allowlist tail is passed through untouched
+    let mut with_proof = data.clone();
+    with_proof.extend_from_slice(&[7; 40]);
+    let (decoded, tail) = unpack_ix_data::<ContributeIxData>(&with_proof).unwrap();
+    assert_eq!(decoded, contribute);
+    assert_eq!(tail, &[7; 40]);
+
+    assert_eq!(
+        ContributeIxData::unpack(&data[..ContributeIxData::LEN - 1]).unwrap_err(),
+        ProgramError::InvalidInstructionData
+    );
+    assert_eq!(
+        ContributeIxData::unpack(&(ContributeIxData { amount: 0, ..contribute.clone() }).pack()),
+        Err(FundraiserError::InvalidAmount.into())
+    );
+
+    // Initialize round trips every tier and bonus period
+    let mut tiers = [TierIxData::default(); MAX_TIERS];
+    tiers[0] = TierIxData { min_amount: 1_000_000, max_backers: 10, label_id: 1 };
+    tiers[1] = TierIxData { min_amount: 5_000_000, max_backers: 0, label_id: 2 };
+    let mut bonus_periods = [BonusPeriod::default(); MAX_BONUS_PERIODS];
+    bonus_periods[0] = BonusPeriod { duration: SECONDS_TO_DAYS.into(), bonus_bps: 2_000.into() };
+    bonus_periods[1] = BonusPeriod {
+        duration: (3 * SECONDS_TO_DAYS).into(),
+        bonus_bps: 1_000.into(),
+    };
+    let initialize = InitializeIxData {
+        amount: 30_000_000,
+        duration: 14,
+        bump: 255,
+        tier_count: 2,
+        tiers,
+        receipt_rate: RECEIPT_RATE_SCALER,
+        receipt_bump: 252,
+        merkle_root: [3; 32],
+        bonus_periods,
+    };
+    let data = initialize.pack();
+    assert_eq!(data.len(), 138);
+    assert_eq!(InitializeIxData::unpack(&data).unwrap(), initialize);
+
+    let rejects = [
+        (InitializeIxData { amount: 2, ..initialize.clone() }, FundraiserError::InvalidAmount),
+        (InitializeIxData { duration: 0, ..initialize.clone() }, FundraiserError::InvalidDuration),
+        (
+            InitializeIxData { duration: MAX_DURATION_DAYS + 1, ..initialize.clone() },
+            FundraiserError::InvalidDuration,
+        ),
+        (
+            InitializeIxData { tier_count: (MAX_TIERS + 1) as u8, ..initialize.clone() },
+            FundraiserError::InvalidTier,
+        ),
+        (
+            InitializeIxData { receipt_rate: 0, ..initialize.clone() },
+            FundraiserError::InvalidReceiptRate,
+        ),
+    ];
+    for (ix_data, error) in rejects {
+        assert_eq!(InitializeIxData::unpack(&ix_data.pack()), Err(error.into()));
+    }
+    let mut unordered = initialize.clone();
+    unordered.bonus_periods.swap(0, 1);
+    assert_eq!(
+        InitializeIxData::unpack(&unordered.pack()),
+        Err(FundraiserError::InvalidBonusSchedule.into())
+    );
+
+    // Every other instruction round trips at its documented length
+    let metadata = get_metadata_ix_data("Wire", 250);
+    assert_eq!(metadata.pack().len(), 276);
+    assert_eq!(MetadataIxData::unpack(&metadata.pack()).unwrap(), metadata);
+    let mut invalid_name = metadata.clone();
+    invalid_name.name[..2].copy_from_slice(&[0xc3, 0x28]);
+    let mut unpadded_symbol = metadata.clone();
+    unpadded_symbol.symbol[MAX_SYMBOL_LEN - 1] = b'X';
+    let mut truncated_uri = metadata.clone();
+    truncated_uri.uri[19] = 0xe2;
+    for ix_data in [invalid_name, unpadded_symbol, truncated_uri] {
+        assert_eq!(
+            MetadataIxData::unpack(&ix_data.pack()),
+            Err(FundraiserError::InvalidMetadata.into())
+        );
+    }
+    let mut full_name = metadata.clone();
+    full_name.name = ["é".as_bytes(); MAX_NAME_LEN / 2].concat().try_into().unwrap();
+    assert_eq!(MetadataIxData::unpack(&full_name.pack()).unwrap(), full_name);
+
+    let transfer = TransferPositionIxData {
+        amount: 5,
+        recipient: [4; 32],
+        recipient_bump: 1,
+        owner_bump: 2,
+    };
+    assert_eq!(transfer.pack().len(), 42);
+    assert_eq!(TransferPositionIxData::unpack(&transfer.pack()).unwrap(), transfer);
+
+    let pledge = CreateMatchPledgeIxData { cap: 1_000, bump: 2 };
+    assert_eq!(CreateMatchPledgeIxData::unpack(&pledge.pack()).unwrap(), pledge);
+    assert_eq!(
+        CreateMatchPledgeIxData::unpack(&(CreateMatchPledgeIxData { cap: 0, bump: 2 }).pack()),
+        Err(FundraiserError::InvalidAmount.into())
+    );
+
+    let round = InitializeRoundIxData { id: 7, end_time: -1, bump: 3 };
+    assert_eq!(InitializeRoundIxData::unpack(&round.pack()).unwrap(), round);
+
+    let distribute = DistributeRoundIxData { index: 2 };
+    assert_eq!(DistributeRoundIxData::unpack(&distribute.pack()).unwrap(), distribute);
+    assert_eq!(
+        DistributeRoundIxData::unpack(&[MAX_ROUND_CAMPAIGNS as u8]).unwrap_err(),
+        ProgramError::InvalidInstructionData
+    );
+
+    let registry_contribution = ContributeRegistryIxData { amount: 8 };
+    assert_eq!(
+        ContributeRegistryIxData::unpack(&registry_contribution.pack()).unwrap(),
+        registry_contribution
+    );
+    let registry = InitializeRegistryIxData { bump: 4, capacity: 100 };
+    assert_eq!(InitializeRegistryIxData::unpack(&registry.pack()).unwrap(), registry);
+    let migrate = MigrateIxData { bump: 5 };
+    assert_eq!(MigrateIxData::unpack(&migrate.pack()).unwrap(), migrate);
+    let get_status = GetStatusIxData { contributor_bump: 6 };
+    assert_eq!(GetStatusIxData::unpack(&get_status.pack()).unwrap(), get_status);
+    let refund = RefundIxData { contributor_bump: 7 };
+    assert_eq!(RefundIxData::unpack(&refund.pack()).unwrap(), refund);
+    assert!(RefundIxData::unpack(&[]).is_err());
+    let close = CloseContributorIxData { contributor_bump: 8 };
+    assert_eq!(CloseContributorIxData::unpack(&close.pack()).unwrap(), close);
+
+    let status = FundraiserStatus {
+        status: CampaignStatus::Failed,
+        amount_to_raise: 1_000,
+        current_amount: 10,
+        time_started: -1,
+        duration: 3,
+        contributor_count: 2,
+        contributed: 5,
+        weight: 6,
+    };
+    let mut packed = status.pack();
+    assert_eq!(packed.len(), FundraiserStatus::LEN);
+    assert_eq!(FundraiserStatus::unpack(&packed).unwrap(), status);
+    packed[0] = CampaignStatus::Cancelled as u8 + 1;
+    assert!(FundraiserStatus::unpack(&packed).is_err());
+}
+
+#[test]
+fn test_fundraiser_cli() {
+    let run = |args: &[&str]| {
+        std::process::Command
+            ::new(env!("CARGO_BIN_EXE_fundraiser-cli"))
+            .args(args)
+            .output()
+            .unwrap()
+    };
+    let maker = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let (fundraiser, bump) = find_fundraiser_address(&maker);
+
+    let output = run(&["pda", "fundraiser", "--maker", &maker.to_string()]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), format!("{fundraiser} (bump {bump})\n"));
+
+    // Transactions carry the builder instruction and are left unsigned
+    let blockhash = solana_sdk::hash::Hash::new_unique();
+    let output = run(
+        &[
+            "contribute",
+            "--contributor",
+            &maker.to_string(),
+            "--maker",
+            &maker.to_string(),
+            "--mint",
+            &mint.to_string(),
+            "--amount",
+            "500",
+            "--blockhash",
+            &blockhash.to_string(),
+        ]
+    );
+    assert!(output.status.success());
+    let encoded = String::from_utf8(output.stdout).unwrap();
+    let bytes = base64::Engine
+        ::decode(&base64::engine::general_purpose::STANDARD, encoded.trim())
+        .unwrap();
+    let transaction: solana_sdk::transaction::Transaction = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(transaction.message.recent_blockhash, blockhash);
+    assert_eq!(transaction.message.account_keys[0], maker);
+    assert!(transaction.signatures.iter().all(|signature| *signature == Default::default()));
+    let expected = Contribute::new(maker, maker, mint, 500).instruction();
+    assert_eq!(transaction.message.instructions[0].data, expected.data);
+
+    // Round and allowlist options reach the builder
+    let round = Pubkey::new_unique();
+    let proof = [[0xab; 32], [0x01; 32]];
+    let hex = |hash: &[u8; 32]| hash.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+    let output = run(
+        &[
+            "contribute",
+            "--contributor",
+            &maker.to_string(),
+            "--maker",
+            &maker.to_string(),
+            "--mint",
+            &mint.to_string(),
+            "--amount",
+            "500",
+            "--round",
+            &round.to_string(),
+            "--allowlist-cap",
+            "1000",
+            "--proof",
+            &format!("{},{}", hex(&proof[0]), hex(&proof[1])),
+            "--blockhash",
+            &blockhash.to_string(),
+        ]
+    );
+    assert!(output.status.success());
+    let encoded = String::from_utf8(output.stdout).unwrap();
+    let bytes = base64::Engine
+        ::decode(&base64::engine::general_purpose::STANDARD, encoded.trim())
+        .unwrap();
+    let transaction: solana_sdk::transaction::Transaction = bincode::deserialize(&bytes).unwrap();
+    let expected = (Contribute {
+        round: Some(round),
+        allowlist: Some(AllowlistProof { cap: 1000, proof: proof.to_vec() }),
+        ..Contribute::new(maker, maker, mint, 500)
+    }).instruction();
+    let compiled = &transaction.message.instructions[0];
+    assert_eq!(compiled.data, expected.data);
+    let keys: Vec<_> = compiled.accounts
+        .iter()
+        .map(|index| transaction.message.account_keys[*index as usize])
+        .collect();
+    let expected_keys: Vec<_> = expected.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(keys, expected_keys);
+    let output = run(
+        &[
+            "contribute",
+            "--contributor",
+            &maker.to_string(),
+            "--maker",
+            &maker.to_string(),
+            "--mint",
+            &mint.to_string(),
+            "--amount",
+            "500",
+            "--proof",
+            "ab",
+            "--blockhash",
+            &blockhash.to_string(),
+        ]
+    );
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "error: invalid value for --proof: ab\n");
+
+    // Account dumps decode from `solana account --output json`
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.initialize(maker.to_bytes(), mint.to_bytes(), 1_000, 5, bump, 0);
+    let data = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, unsafe {
+        to_bytes(&fundraiser_state)
+    });
+    let path = std::env::temp_dir().join(format!("fundraiser-cli-{fundraiser}.json"));
+    std::fs
+        ::write(&path, format!(r#"{{"account": {{"data": ["{data}", "base64"]}}}}"#))
+        .unwrap();
+    let output = run(&["decode", "fundraiser", path.to_str().unwrap(), "--now", "0"]);
+    std::fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    let printed = String::from_utf8(output.stdout).unwrap();
+    assert!(printed.contains(&format!("maker:             {maker}")));
+    assert!(printed.contains("status:            Active"));
+
+    let output = run(&["error", "0x1d"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("InvalidDuration (29): {}\n", FundraiserError::InvalidDuration.message())
+    );
+}
+
+#[test]
+fn test_error_codes() {
+    // Codes are part of the interface, changing one breaks deployed clients
+    let pinned = [
+        (0, FundraiserError::TargetNotMet),
+        (1, FundraiserError::TargetMet),
+        (2, FundraiserError::ContributionTooBig),
+        (3, FundraiserError::ContributionTooSmall),
+        (4, FundraiserError::MaximumContributionsReached),
+        (5, FundraiserError::FundraiserNotEnded),
+        (6, FundraiserError::FundraiserEnded),
+        (7, FundraiserError::InvalidAmount),
+        (8, FundraiserError::InvalidMaker),
+        (9, FundraiserError::MetadataLocked),
+        (10, FundraiserError::InvalidTier),
+        (11, FundraiserError::TierFull),
+        (12, FundraiserError::TierMinimumNotMet),
+        (13, FundraiserError::InvalidReceiptRate),
+        (14, FundraiserError::NotAllowlisted),
+        (15, FundraiserError::AllowlistCapExceeded),
+        (16, FundraiserError::InvalidBonusSchedule),
+        (17, FundraiserError::RoundFull),
+        (18, FundraiserError::RoundEnded),
+        (19, FundraiserError::RoundNotEnded),
+        (20, FundraiserError::InvalidRound),
+        (21, FundraiserError::NotEligibleForMatch),
+        (22, FundraiserError::MatchAlreadyPaid),
+        (23, FundraiserError::FundraiserSettled),
+        (24, FundraiserError::FundraiserNotSettled),
+        (25, FundraiserError::RegistryMode),
+        (26, FundraiserError::RegistryNotSupported),
+        (27, FundraiserError::NotInRegistry),
+        (28, FundraiserError::InvalidAccountVersion),
+        (29, FundraiserError::InvalidDuration),
+        (30, FundraiserError::InvalidMintOwner),
+        (31, FundraiserError::MintNotInitialized),
+        (32, FundraiserError::InvalidVaultMint),
+        (33, FundraiserError::RegistryFull),
+        (34, FundraiserError::InvalidVaultOwner),
+        (35, FundraiserError::InvalidMetadata),
+        (36, FundraiserError::GiftInRound),
+    ];
+    for (code, error) in pinned {
+        assert_eq!(error as u32, code, "{error:?}");
+        assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        assert_eq!(FundraiserError::try_from(code), Ok(error));
+        assert_eq!(error.to_string(), error.message());
+    }
+    assert_eq!(FundraiserError::try_from(pinned.len() as u32), Err(ProgramError::InvalidArgument));
+    assert_eq!(FundraiserError::try_from(u32::MAX), Err(ProgramError::InvalidArgument));
+}
+
+#[test]
+fn test_events() {
+    let created = FundraiserCreated {
+        fundraiser: [1; 32],
+        maker: [2; 32],
+        mint_to_raise: [3; 32],
+        amount_to_raise: 10_000_000,
+        time_started: -5,
+        duration: 7,
+    };
+    let contributed = Contributed {
+        fundraiser: [1; 32],
+        contributor: [4; 32],
+        amount: 500,
+        current_amount: 1_500,
+    };
+    let refunded = Refunded { fundraiser: [1; 32], contributor: [4; 32], amount: 500 };
+    let finalized = Finalized { fundraiser: [1; 32], maker: [2; 32], amount: 10_000_000 };
+    let cancelled = Cancelled { fundraiser: [1; 32] };
+    let matched = Matched {
+        fundraiser: [1; 32],
+        sponsor: [5; 32],
+        amount: 500,
+        current_amount: 2_000,
+    };
+
+    fn encode<E: Event>(event: &E) -> Vec<u8> {
+        assert!(E::LEN < MAX_EVENT_LEN);
+        let mut data = vec![E::DISCRIMINATOR as u8; 1 + E::LEN];
+        event.pack_into(&mut data[1..]);
+        data
+    }
+    let encoded = [
+        (encode(&created), FundraiserEvent::FundraiserCreated(created)),
+        (encode(&contributed), FundraiserEvent::Contributed(contributed)),
+        (encode(&refunded), FundraiserEvent::Refunded(refunded)),
+        (encode(&finalized), FundraiserEvent::Finalized(finalized)),
+        (encode(&cancelled), FundraiserEvent::Cancelled(cancelled)),
+        (encode(&matched), FundraiserEvent::Matched(matched)),
+    ];
+    for (data, event) in &encoded {
+        assert_eq!(FundraiserEvent::unpack(data).as_ref(), Ok(event));
+        assert!(FundraiserEvent::unpack(&data[..data.len() - 1]).is_err());
+    }
+    assert!(FundraiserEvent::unpack(&[6; 81]).is_err());
+    assert!(FundraiserEvent::unpack(&[]).is_err());
+
+    // Only data logged by this program counts, not by the programs it invokes
+    let program_data = |data: &[u8]| {
+        let data = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+        format!("Program data: {data}")
+    };
+    let program = PROGRAM_ID.to_string();
+    let token_program = TOKEN_PROGRAM_ID.to_string();
+    let logs = vec![
+        format!("Program {program} invoke [1]"),
+        "Program log: Contribute instruction".to_string(),
+        // Program logs shaped like the runtime's lines leave the stack alone
+        "Program log: success".to_string(),
+        format!("Program log: {token_program} invoke [2]"),
+        format!("Program {token_program} invoke [2]"),
+        program_data(&encoded[3].0),
+        format!("Program {token_program} success"),
+        program_data(&encoded[1].0),
+        format!("Program {program} consumed 1000 of 200000 compute units"),
+        format!("Program {program} success"),
+        format!("Program {token_program} invoke [1]"),
+        program_data(&encoded[2].0),
+        format!("Program {token_program} success"),
+        format!("Program {program} invoke [1]"),
+        program_data(&encoded[4].0),
+        format!("Program {program} failed: custom program error: 0x1"),
+        program_data(&encoded[0].0),
+    ];
+    assert_eq!(parse_logs(&logs), vec![encoded[1].1.clone(), encoded[4].1.clone()]);
+    assert_eq!(parse_log_data("not base64"), None);
+}
+
+#[test]
+fn test_get_status() {
+    let mollusk = mollusk();
+    let maker = Pubkey::new_unique();
+    let owner = Pubkey::new_unique();
+    let (fundraiser, bump) = find_fundraiser_address(&maker);
+    let (contributor_acc, _) = find_contributor_address(&fundraiser, &owner);
+    let now = mollusk.sysvars.clock.unix_timestamp;
+
+    let mut fundraiser_state = Fundraiser::default();
+    fundraiser_state.initialize(maker.to_bytes(), [2; 32], 10_000_000, 5, bump, now);
+    fundraiser_state.current_amount.set(1_500_000);
+    fundraiser_state.contributor_count.set(1);
+    let mut fundraiser_account = Account::new(LAMPORTS_PER_SOL, Fundraiser::LEN, &PROGRAM);
+    fundraiser_account.data = unsafe { to_bytes(&fundraiser_state) }.to_vec();
+
+    let mut contributor_state = Contributor::default();
+    contributor_state.initialize(owner.to_bytes(), owner.to_bytes());
+    contributor_state.amount.set(1_500_000);
+    contributor_state.weight.set(1_650_000);
+    let mut contributor_account = Account::new(LAMPORTS_PER_SOL, Contributor::LEN, &PROGRAM);
+    contributor_account.data = unsafe { to_bytes(&contributor_state) }.to_vec();
+
+    let expected = FundraiserStatus {
+        status: CampaignStatus::Active,
+        amount_to_raise: 10_000_000,
+        current_amount: 1_500_000,
+        time_started: now,
+        duration: 5,
+        contributor_count: 1,
+        contributed: 1_500_000,
+        weight: 1_650_000,
+    };
+    let result = mollusk.process_and_validate_instruction(
+        &(GetStatus { maker, contributor: Some(owner) }).instruction(),
+        &[
+            (fundraiser, fundraiser_account.clone()),
+            (contributor_acc, contributor_account.clone())
+        ],
+        &[Check::success()]
+    );
+    assert_eq!(FundraiserStatus::unpack(&result.return_data).unwrap(), expected);
+
+    // Without a contributor only the campaign is reported
+    let result = mollusk.process_and_validate_instruction(
+        &(GetStatus { maker, contributor: None }).instruction(),
+        &[(fundraiser, fundraiser_account.clone())],
+        &[Check::success()]
+    );
+    assert_eq!(
+        FundraiserStatus::unpack(&result.return_data).unwrap(),
+        FundraiserStatus { contributed: 0, weight: 0, ..expected }
+    );
+
+    // A contributor account of another fundraiser is rejected
+    let mut instruction = (GetStatus { maker, contributor: Some(owner) }).instruction();
+    let other_contributor = find_contributor_address(&Pubkey::new_unique(), &owner).0;
+    instruction.accounts[1].pubkey = other_contributor;
+    let result = mollusk.process_instruction(
+        &instruction,
+        &[(fundraiser, fundraiser_account), (other_contributor, contributor_account)]
+    );
+    assert_eq!(
+        result.program_result,
+        ProgramResult::Failure(solana_sdk::program_error::ProgramError::InvalidSeeds)
+    );
+}
+
+/// Loader input holding a single account with `key`, the only way to get a
+/// `pinocchio` account info outside the runtime. The account info points
+/// into the returned buffer.
+fn cpi_account_info(key: &Pubkey) -> (Vec<u64>, pinocchio::account_info::AccountInfo) {
+    // Account count (8), account header (88), no data, realloc room, rent
+    // epoch (8) with its alignment, instruction data length (8), program id (32)
+    let len = 8 + 88 + pinocchio::account_info::MAX_PERMITTED_DATA_INCREASE + 16 + 8 + 8 + 32;
+    let mut input = vec![0u64; len.div_ceil(8)];
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(input.as_mut_ptr() as *mut u8, input.len() * 8)
+    };
+    bytes[..8].copy_from_slice(&1u64.to_le_bytes());
+    // Not a duplicate
+    bytes[8] = u8::MAX;
+    bytes[16..48].copy_from_slice(key.as_ref());
+
+    let mut accounts = [core::mem::MaybeUninit::uninit()];
+    let account_info = unsafe {
+        pinocchio::entrypoint::deserialize::<1>(input.as_mut_ptr() as *mut u8, &mut accounts);
+        accounts[0].assume_init_read()
+    };
+    (input, account_info)
+}
+
+/// Account infos for the accounts of `instruction`, in order
+fn cpi_account_infos(
+    instruction: &solana_sdk::instruction::Instruction
+) -> (Vec<Vec<u64>>, Vec<pinocchio::account_info::AccountInfo>) {
+    instruction.accounts
+        .iter()
+        .map(|meta| cpi_account_info(&meta.pubkey))
+        .unzip()
+}
+
+/// Asserts that a cpi helper encodes the same instruction as the client builder
+fn assert_cpi_instruction(
+    instruction: &pinocchio::instruction::Instruction,
+    account_infos: &[&pinocchio::account_info::AccountInfo],
+    expected: &solana_sdk::instruction::Instruction
+) {
+    assert_eq!(instruction.program_id, &expected.program_id.to_bytes());
+    let metas: Vec<_> = instruction.accounts
+        .iter()
+        .map(|meta| (Pubkey::new_from_array(*meta.pubkey), meta.is_signer, meta.is_writable))
+        .collect();
+    let expected_metas: Vec<_> = expected.accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect();
+    assert_eq!(metas, expected_metas);
+    assert_eq!(instruction.data, &expected.data[..]);
+    let keys: Vec<_> = account_infos.iter().map(|info| *info.key()).collect();
+    let meta_keys: Vec<_> = instruction.accounts.iter().map(|meta| *meta.pubkey).collect();
+    assert_eq!(keys, meta_keys);
+}
+
+#[test]
+fn test_cpi_matches_client() {
+    let maker = Pubkey::new_unique();
+    let contributor = Pubkey::new_unique();
+    let mint_to_raise = Pubkey::new_unique();
+    let (fundraiser, fundraiser_bump) = find_fundraiser_address(&maker);
+
+    // Every optional part of a contribution, the proof fills the data buffer
+    let beneficiary = Pubkey::new_unique();
+    let (_, contributor_bump) = find_contributor_address(&fundraiser, &beneficiary);
+    let proof = [[7; 32]; MAX_MERKLE_PROOF_LEN];
+    let full_contribute = Contribute {
+        tier: 1,
+        beneficiary: Some(beneficiary),
+        fee_payer: Some(Pubkey::new_unique()),
+        round: Some(Pubkey::new_unique()),
+        allowlist: Some(AllowlistProof { cap: 2_000_000, proof: proof.to_vec() }),
+        ..Contribute::new(contributor, maker, mint_to_raise, 1_000_000)
+    };
+    let expected = full_contribute.instruction();
+    let (_buffers, infos) = cpi_account_infos(&expected);
+    let result = (pinocchio_fundraiser::cpi::Contribute {
+        contributor: &infos[0],
+        mint_to_raise: &infos[1],
+        fundraiser: &infos[2],
+        contributor_acc: &infos[3],
+        contributor_ata: &infos[4],
+        vault: &infos[5],
+        token_program: &infos[6],
+        system_program: &infos[7],
+        receipt_mint: &infos[8],
+        contributor_receipt_ata: &infos[9],
+        round: Some(&infos[10]),
+        fee_payer: Some(&infos[12]),
+        amount: 1_000_000,
+        fundraiser_bump,
+        contributor_bump,
+        tier: 1,
+        beneficiary: Some(&infos[11]),
+        allowlist: Some((2_000_000, &proof)),
+    }).with_instruction(|instruction, account_infos| {
+        assert_eq!(instruction.data.len(), 1 + ContributeIxData::LEN + 8 + 32 * proof.len());
+        assert_cpi_instruction(instruction, account_infos, &expected);
+    });
+    assert!(result.is_ok());
+
+    // No optional part
+    let expected = Contribute::new(contributor, maker, mint_to_raise, 1_000_000).instruction();
+    let (_, contributor_bump) = find_contributor_address(&fundraiser, &contributor);
+    let (_buffers, infos) = cpi_account_infos(&expected);
+    let result = (pinocchio_fundraiser::cpi::Contribute {
+        contributor: &infos[0],
+        mint_to_raise: &infos[1],
+        fundraiser: &infos[2],
+        contributor_acc: &infos[3],
+        contributor_ata: &infos[4],
+        vault: &infos[5],
+        token_program: &infos[6],
+        system_program: &infos[7],
+        receipt_mint: &infos[8],
+        contributor_receipt_ata: &infos[9],
+        round: None,
+        fee_payer: None,
+        amount: 1_000_000,
+        fundraiser_bump,
+        contributor_bump,
+        tier: NO_TIER,
+        beneficiary: None,
+        allowlist: None,
+    }).with_instruction(|instruction, account_infos| {
+        assert_cpi_instruction(instruction, account_infos, &expected);
+    });
+    assert!(result.is_ok());
+
+    // Refund, with and without a separate rent payer
+    let (_, contributor_bump) = find_contributor_address(&fundraiser, &contributor);
+    for rent_payer in [None, Some(Pubkey::new_unique())] {
+        let expected = (Refund { contributor, maker, mint_to_raise, rent_payer }).instruction();
+        let (_buffers, infos) = cpi_account_infos(&expected);
+        (pinocchio_fundraiser::cpi::Refund {
+            contributor: &infos[0],
+            maker: &infos[1],
+            mint_to_raise: &infos[2],
+            fundraiser: &infos[3],
+            contributor_acc: &infos[4],
+            contributor_ata: &infos[5],
+            vault: &infos[6],
+            system_program: &infos[7],
+            token_program: &infos[8],
+            receipt_mint: &infos[9],
+            contributor_receipt_ata: &infos[10],
+            rent_payer: infos.get(11),
+            contributor_bump,
+        }).with_instruction(|instruction, account_infos| {
+            assert_cpi_instruction(instruction, account_infos, &expected);
+        });
+    }
+
+    // Claim, with and without a registry
+    for uses_registry in [false, true] {
+        let expected = (CheckContribution { maker, mint_to_raise, uses_registry }).instruction();
+        let (_buffers, infos) = cpi_account_infos(&expected);
+        (pinocchio_fundraiser::cpi::CheckContribution {
+            maker: &infos[0],
+            mint_to_raise: &infos[1],
+            fundraiser: &infos[2],
+            vault: &infos[3],
+            maker_ata: &infos[4],
+            token_program: &infos[5],
+            system_program: &infos[6],
+            registry: infos.get(7),
+        }).with_instruction(|instruction, account_infos| {
+            assert_cpi_instruction(instruction, account_infos, &expected);
+        });
+    }
+
+    // Status, with and without a contributor
+    for owner in [None, Some(contributor)] {
+        let expected = (GetStatus { maker, contributor: owner }).instruction();
+        let (_buffers, infos) = cpi_account_infos(&expected);
+        (pinocchio_fundraiser::cpi::GetStatus {
+            fundraiser: &infos[0],
+            contributor: infos.get(1),
+            contributor_bump: owner.map_or(0, |_| contributor_bump),
+        }).with_instruction(|instruction, account_infos| {
+            assert_cpi_instruction(instruction, account_infos, &expected);
+        });
+    }
 }